@@ -1,14 +1,19 @@
 #![allow(dead_code)]
 #![allow(non_snake_case)]
 #![allow(unused_imports)]
+#![allow(clippy::needless_return)]
 
 use bzip2::read::BzDecoder;
 use bzip2::Compression;
 use histogram::Histogram;
-use rand::{prelude::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+use rand::{prelude::StdRng, seq::SliceRandom, thread_rng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
-use std::{collections::VecDeque, io::BufRead};
-#[derive(Debug, PartialEq, Clone)]
+use std::{cell::RefCell, collections::VecDeque, io::BufRead};
+
+pub mod policy_text;
+pub mod search;
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Card {
     ACE,
     TWO,
@@ -40,7 +45,7 @@ impl Card {
         }
     }
 
-    fn value(&self) -> i64 {
+    pub fn value(&self) -> i64 {
         match self {
             Card::ACE => 1,
             Card::TWO => 2,
@@ -57,6 +62,7 @@ impl Card {
     }
 }
 
+#[derive(Clone)]
 pub struct CardList {
     ccards: Vec<Card>,
 }
@@ -66,24 +72,38 @@ impl CardList {
         CardList { ccards: Vec::new() }
     }
 
-    fn draw_top_card(&mut self) -> Card {
-        self.ccards.pop().unwrap()
+    fn draw_top_card(&mut self) -> Option<Card> {
+        self.ccards.pop()
     }
 
     fn place_card_on_top(&mut self, c: Card) {
         self.ccards.push(c)
     }
 
-    fn remove_card_of_type(&mut self, c: Card) {
-        self.ccards
-            .remove(self.ccards.iter().position(|x| *x == c).unwrap());
+    fn remove_card_of_type(&mut self, c: &Card) -> bool {
+        match self.ccards.iter().position(|x| x == c) {
+            Some(idx) => {
+                self.ccards.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ccards.is_empty()
+    }
+
+    /// The cards, bottom card first.
+    pub fn cards(&self) -> &[Card] {
+        &self.ccards
     }
 
-    fn is_empty(&self) -> bool {
-        self.ccards.len() == 0
+    pub fn len(&self) -> usize {
+        self.ccards.len()
     }
 
-    fn score_hand(&self) -> i64 {
+    pub fn score_hand(&self) -> i64 {
         self.ccards.iter().map(|c| c.value()).sum()
     }
 }
@@ -94,22 +114,115 @@ impl std::fmt::Debug for CardList {
         write!(f, "[{}]", symbols.join(""))
     }
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Buy {
     JackWith(Card),
     QueenWith(Card),
     KingWith(Card),
 }
 
-type BuyPolicyType = Box<dyn Fn(&Game, usize) -> Option<Buy>>;
+impl Buy {
+    /// A buy of `piece` paid with `card`.
+    pub fn new(piece: BuyablePiece, card: Card) -> Buy {
+        match piece {
+            BuyablePiece::JACK => Buy::JackWith(card),
+            BuyablePiece::QUEEN => Buy::QueenWith(card),
+            BuyablePiece::KING => Buy::KingWith(card),
+        }
+    }
+
+    /// The face card being bought.
+    pub fn piece(&self) -> BuyablePiece {
+        match self {
+            Buy::JackWith(_) => BuyablePiece::JACK,
+            Buy::QueenWith(_) => BuyablePiece::QUEEN,
+            Buy::KingWith(_) => BuyablePiece::KING,
+        }
+    }
+
+    /// The card offered as payment.
+    pub fn card(&self) -> &Card {
+        match self {
+            Buy::JackWith(card) | Buy::QueenWith(card) | Buy::KingWith(card) => card,
+        }
+    }
+}
+
+/// A way of playing. Every method takes `&self` so one policy can sit in several seats and
+/// games at once; agents that keep state or learn do so behind interior mutability.
+pub trait Policy: Send + Sync {
+    fn name(&self) -> String;
+
+    /// The buy to make, or `None` to pass.
+    fn choose_buy(&self, view: &PlayerView) -> Option<Buy>;
+
+    /// The peeked cards in their new order, top card first. Anything that is not a
+    /// permutation of `peeked` is rejected by the engine.
+    fn choose_reorder(&self, view: &PlayerView, peeked: &[Card]) -> Vec<Card> {
+        ReorderPolicy::default().reorder(view, peeked)
+    }
+
+    /// Called with every event the seat of `view` gets to see, as decided by
+    /// `GameEvent::is_visible_to`. Each game ends with a `GameEnd` event.
+    fn observe(&self, _view: &PlayerView, _event: &GameEvent) {}
+
+    /// Whether `observe` needs to be called at all. Policies that override `observe` must
+    /// return `true`; when no seat wants events and no log is recorded, the engine skips
+    /// them.
+    fn wants_events(&self) -> bool {
+        false
+    }
+
+    /// The serializable description of this policy, where there is one.
+    fn to_config(&self) -> Option<PolicyConfig> {
+        None
+    }
+}
+
+/// A policy that buys with a closure and reorders with the default.
+pub struct FnPolicy<F> {
+    name: String,
+    buy: F,
+}
+
+impl<F> FnPolicy<F>
+where
+    F: Fn(&PlayerView) -> Option<Buy> + Send + Sync,
+{
+    pub fn new(name: &str, buy: F) -> FnPolicy<F> {
+        FnPolicy {
+            name: String::from(name),
+            buy,
+        }
+    }
+}
+
+impl<F> Policy for FnPolicy<F>
+where
+    F: Fn(&PlayerView) -> Option<Buy> + Send + Sync,
+{
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn choose_buy(&self, view: &PlayerView) -> Option<Buy> {
+        (self.buy)(view)
+    }
+}
 
 pub struct Player<'a> {
     pub name: String,
     pub hand: CardList,
     pub jacks: i64,
     pub queens: i64,
-    pub buy_policy: &'a BuyPolicyType,
-    pub reorder_policy: fn(&mut Game, usize),
+    /// Kings bought by this player. They score nothing but do count towards `GameOutcome`.
+    pub kings: i64,
+    /// Illegal buys that were turned into a pass under `BuyValidation::Lenient`.
+    pub rejected_buys: i64,
+    /// Cards this player put back during their last Queen peek that are still on top of the
+    /// draw pile, top card first. Forgotten once another player reorders the pile.
+    pub known_top_cards: Vec<Card>,
+    pub policy: &'a dyn Policy,
 }
 
 impl<'a> std::fmt::Debug for Player<'a> {
@@ -122,40 +235,447 @@ impl<'a> std::fmt::Debug for Player<'a> {
     }
 }
 
+/// How the joker is placed in the draw pile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DeckSetup {
+    /// Reshuffle the whole deck until the joker is not among the top `joker_free_top_cards`.
+    Reshuffle { joker_free_top_cards: usize },
+    /// The procedure from rules.md: shuffle the numbered cards, set aside the top
+    /// `top_fraction` of them, shuffle the joker into the rest and stack the set-aside
+    /// cards back on top.
+    Split { top_fraction: f64 },
+}
+
+/// Tunable game parameters, so balance experiments don't need a fork of the engine.
+/// `RulesConfig::default()` is the game from rules.md, with the joker placed by the
+/// `DeckSetup::Reshuffle` approximation that all the stored records were scored with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RulesConfig {
+    /// Copies of each rank A-10 in the deck.
+    pub copies_per_rank: i64,
+    pub deck_setup: DeckSetup,
+    /// Face cards of each kind available to buy.
+    pub jacks: i64,
+    pub queens: i64,
+    pub kings: i64,
+    /// Cost of the first face card of each kind.
+    pub first_cost: i64,
+    /// Cost increase for every face card of the same kind already bought.
+    pub cost_step: i64,
+}
+
+impl Default for RulesConfig {
+    fn default() -> RulesConfig {
+        RulesConfig {
+            copies_per_rank: 4,
+            deck_setup: DeckSetup::Reshuffle {
+                joker_free_top_cards: 14,
+            },
+            jacks: 4,
+            queens: 4,
+            kings: 4,
+            first_cost: 1,
+            cost_step: 1,
+        }
+    }
+}
+
+/// How `verbose_play_game` treats a `Buy` that breaks the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuyValidation {
+    /// Abort the game with `GameError::IllegalBuy`.
+    Strict,
+    /// Treat the buy as a pass and count it in `Player::rejected_buys`.
+    Lenient,
+}
+
 #[derive(Debug)]
 pub struct Game<'a> {
     pub players: Vec<Player<'a>>,
     pub unbought_kings: i64,
     pub remaining_cards: CardList,
+    /// Cards paid for face cards and hands wiped by aces, face up for everyone to see.
+    pub discards: Vec<Card>,
+    pub rules: RulesConfig,
+    pub buy_validation: BuyValidation,
+    /// Events of the game so far, only recorded when set to `Some`.
+    pub event_log: Option<Vec<GameEvent>>,
+    pub current_player_idx: usize,
+    /// The turn being played, counting from 0 for the first player's first turn.
+    pub turn: i64,
+    pub phase: Phase,
+    /// Randomness for stochastic policies, see `PlayerView::rng`.
+    policy_rng: RefCell<GameRng>,
 }
 
-fn cheapest_card_that_can_pay_x(cards: &CardList, cost: i64) -> Option<Card> {
-    let mut best: Option<Card> = None;
-    for c in &cards.ccards {
-        let new = c.clone();
-        if new.value() >= cost {
-            match &best {
-                Some(old) => {
-                    if new.value() < old.value() {
-                        best = Some(new)
+impl<'a> Game<'a> {
+    /// A game under `rules`, with `remaining_cards` dealt by `init_deck` for the same rules.
+    pub fn new(
+        players: Vec<Player<'a>>,
+        rules: &RulesConfig,
+        remaining_cards: CardList,
+    ) -> Game<'a> {
+        Game {
+            players,
+            unbought_kings: rules.kings,
+            remaining_cards,
+            discards: Vec::new(),
+            rules: rules.clone(),
+            buy_validation: BuyValidation::Strict,
+            event_log: None,
+            current_player_idx: 0,
+            turn: 0,
+            phase: Phase::Reorder,
+            policy_rng: RefCell::new(GameRng::seed_from_u64(0)),
+        }
+    }
+
+    /// Reseeds the randomness policies draw on. Games dealt from a `game_rng` seed it with
+    /// the next value of that RNG, so stochastic policies replay with the deck.
+    pub fn seed_policy_rng(&mut self, seed: u64) {
+        self.policy_rng = RefCell::new(GameRng::seed_from_u64(seed));
+    }
+
+    pub fn jacks_bought(&self) -> i64 {
+        self.players.iter().map(|p| p.jacks).sum()
+    }
+
+    pub fn queens_bought(&self) -> i64 {
+        self.players.iter().map(|p| p.queens).sum()
+    }
+
+    pub fn kings_bought(&self) -> i64 {
+        self.rules.kings - self.unbought_kings
+    }
+
+    /// Starts recording a `GameEvent` log into `event_log`, beginning with the `Start`
+    /// event. Call it before the first decision so the log can be replayed.
+    pub fn record_events(&mut self) {
+        let start = GameEvent::Start {
+            players: self.players.iter().map(|p| p.name.clone()).collect(),
+            deck: self.top_cards(self.remaining_cards.ccards.len()),
+            rules: self.rules.clone(),
+        };
+        self.event_log = Some(vec![start]);
+    }
+
+    fn is_recording(&self) -> bool {
+        self.event_log.is_some()
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        for (player_idx, player) in self.players.iter().enumerate() {
+            if player.policy.wants_events() && event.is_visible_to(player_idx) {
+                player.policy.observe(&self.view(player_idx), &event);
+            }
+        }
+        if let Some(log) = &mut self.event_log {
+            log.push(event);
+        }
+    }
+
+    /// The top `n` cards of the draw pile, in the order they will be drawn.
+    fn top_cards(&self, n: usize) -> Vec<Card> {
+        self.remaining_cards
+            .ccards
+            .iter()
+            .rev()
+            .take(n)
+            .cloned()
+            .collect()
+    }
+
+    /// What `player_idx` is allowed to know about the game.
+    pub fn view(&self, player_idx: usize) -> PlayerView<'_> {
+        PlayerView {
+            game: self,
+            player_idx,
+        }
+    }
+
+    /// Draws the top card, which every player who knew about it sees leave the pile.
+    fn pop_top_card(&mut self) -> Option<Card> {
+        for player in &mut self.players {
+            if !player.known_top_cards.is_empty() {
+                player.known_top_cards.remove(0);
+            }
+        }
+        self.remaining_cards.draw_top_card()
+    }
+
+    /// Replaces the top cards with `cards`, top card first, as seen by `player_idx` only.
+    fn reorder_top_cards(&mut self, player_idx: usize, cards: &[Card]) {
+        for _ in 0..cards.len() {
+            self.remaining_cards.draw_top_card();
+        }
+        for card in cards.iter().rev() {
+            self.remaining_cards.place_card_on_top(card.clone());
+        }
+        for (idx, player) in self.players.iter_mut().enumerate() {
+            if idx == player_idx {
+                player.known_top_cards = cards.to_vec();
+            } else {
+                player.known_top_cards.clear();
+            }
+        }
+    }
+
+    fn apply_buy(&mut self, player_idx: usize, buy: &Buy) {
+        self.discards.push(buy.card().clone());
+        let player = &mut self.players[player_idx];
+        player.hand.remove_card_of_type(buy.card());
+        match buy {
+            Buy::KingWith(_) => {
+                self.unbought_kings -= 1;
+                player.kings += 1;
+            }
+            Buy::JackWith(_) => player.jacks += 1,
+            Buy::QueenWith(_) => player.queens += 1,
+        }
+    }
+
+    fn final_result(&self) -> GameResult {
+        if self.unbought_kings > 0 {
+            // println!("| state: {:?}", game);
+            // println!("the GAI turned humans into paperclips");
+            GameResult::Paperclips
+        } else {
+            // println!("| state: {:?}", game);
+            // println!("scores:");
+            let mut highest_score = -1000;
+            let mut num_highest_score = -1;
+            for player in &self.players {
+                let score: i64 = player.hand.score_hand();
+                // println!("  {}: {}", player.name, score);
+                if score > highest_score {
+                    highest_score = score;
+                    num_highest_score = 0;
+                }
+                if score == highest_score {
+                    num_highest_score += 1;
+                }
+            }
+            if num_highest_score >= 2 {
+                GameResult::Draw
+            } else {
+                let mut result = GameResult::Draw;
+                for player in &self.players {
+                    let score: i64 = player.hand.score_hand();
+                    if score == highest_score {
+                        result = GameResult::WinnerNamed(player.name.clone());
                     }
                 }
-                None => best = Some(new),
+                result
+            }
+        }
+    }
+
+    /// The outcome of a game the current player just ended by drawing the joker.
+    fn final_outcome(&self) -> GameOutcome {
+        let scores: Vec<i64> = self.players.iter().map(|p| p.hand.score_hand()).collect();
+        let mut distinct_scores = scores.clone();
+        distinct_scores.sort_unstable_by(|a, b| b.cmp(a));
+        distinct_scores.dedup();
+        let ranking = distinct_scores
+            .iter()
+            .map(|&score| (0..scores.len()).filter(|&i| scores[i] == score).collect())
+            .collect();
+        GameOutcome {
+            result: self.final_result(),
+            scores,
+            ranking,
+            face_cards: self
+                .players
+                .iter()
+                .map(|p| FaceCards {
+                    jacks: p.jacks,
+                    queens: p.queens,
+                    kings: p.kings,
+                })
+                .collect(),
+            turns: self.turn + 1,
+            joker_drawn_by: self.current_player_idx,
+        }
+    }
+}
+
+/// The part of the game a player can see at a real table: their own hand, the face cards
+/// everyone has bought, the size of everyone's hand and of the draw pile, and the cards
+/// they remember from their own Queen peeks. Policies only ever get a `PlayerView`.
+pub struct PlayerView<'g> {
+    game: &'g Game<'g>,
+    player_idx: usize,
+}
+
+/// The public information about another player.
+pub struct OpponentView<'g> {
+    pub player_idx: usize,
+    pub name: &'g str,
+    pub hand_size: usize,
+    pub jacks: i64,
+    pub queens: i64,
+    pub kings: i64,
+}
+
+impl<'g> PlayerView<'g> {
+    fn me(&self) -> &'g Player<'g> {
+        &self.game.players[self.player_idx]
+    }
+
+    pub fn player_idx(&self) -> usize {
+        self.player_idx
+    }
+
+    pub fn name(&self) -> &'g str {
+        &self.me().name
+    }
+
+    pub fn hand(&self) -> &'g CardList {
+        &self.me().hand
+    }
+
+    pub fn jacks(&self) -> i64 {
+        self.me().jacks
+    }
+
+    pub fn queens(&self) -> i64 {
+        self.me().queens
+    }
+
+    pub fn kings(&self) -> i64 {
+        self.me().kings
+    }
+
+    pub fn known_top_cards(&self) -> &'g [Card] {
+        &self.me().known_top_cards
+    }
+
+    /// Every card paid for a face card or wiped by an ace so far.
+    pub fn discards(&self) -> &'g [Card] {
+        &self.game.discards
+    }
+
+    /// The game's randomness for policies that choose at random.
+    pub fn rng(&self) -> std::cell::RefMut<'g, GameRng> {
+        self.game.policy_rng.borrow_mut()
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.game.players.len()
+    }
+
+    /// The other players, in turn order starting after this one.
+    pub fn opponents(&self) -> impl Iterator<Item = OpponentView<'g>> + 'g {
+        let players = &self.game.players;
+        let me = self.player_idx;
+        (1..players.len()).map(move |offset| {
+            let player_idx = (me + offset) % players.len();
+            let player = &players[player_idx];
+            OpponentView {
+                player_idx,
+                name: &player.name,
+                hand_size: player.hand.len(),
+                jacks: player.jacks,
+                queens: player.queens,
+                kings: player.kings,
+            }
+        })
+    }
+
+    pub fn unbought_kings(&self) -> i64 {
+        self.game.unbought_kings
+    }
+
+    pub fn jacks_bought(&self) -> i64 {
+        self.game.jacks_bought()
+    }
+
+    pub fn queens_bought(&self) -> i64 {
+        self.game.queens_bought()
+    }
+
+    pub fn kings_bought(&self) -> i64 {
+        self.game.kings_bought()
+    }
+
+    /// The turn being played, counting from 0 for the first player's first turn.
+    pub fn turn(&self) -> i64 {
+        self.game.turn
+    }
+
+    /// Cards left in the draw pile.
+    pub fn cards_left(&self) -> usize {
+        self.game.remaining_cards.len()
+    }
+
+    pub fn rules(&self) -> &'g RulesConfig {
+        &self.game.rules
+    }
+
+    /// Cost of the next face card of a kind, or `None` once it is sold out.
+    pub fn next_cost(&self, piece: &BuyablePiece) -> Option<i64> {
+        let rules = &self.game.rules;
+        match piece {
+            BuyablePiece::JACK => next_cost(rules, self.jacks_bought(), rules.jacks),
+            BuyablePiece::QUEEN => next_cost(rules, self.queens_bought(), rules.queens),
+            BuyablePiece::KING => next_cost(rules, self.kings_bought(), rules.kings),
+        }
+    }
+
+    /// Checks a buy against the current face-card supply and costs.
+    pub fn validate_buy(&self, buy: &Buy) -> Result<(), IllegalBuyReason> {
+        let cost = self
+            .next_cost(&buy.piece())
+            .ok_or(IllegalBuyReason::SoldOut)?;
+        let card = buy.card();
+        if !self.hand().ccards.contains(card) {
+            return Err(IllegalBuyReason::CardNotInHand);
+        }
+        if card.value() < cost {
+            return Err(IllegalBuyReason::Underpaid {
+                cost,
+                paid: card.value(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Every legal action: `None` (the pass) followed by each distinct card in hand that
+    /// can pay for each face card still available, jacks first, cheapest card first.
+    pub fn legal_buys(&self) -> Vec<Option<Buy>> {
+        let mut cards = self.hand().ccards.clone();
+        cards.sort_by_key(|c| c.value());
+        cards.dedup();
+
+        let mut buys = vec![None];
+        for piece in [BuyablePiece::JACK, BuyablePiece::QUEEN, BuyablePiece::KING].iter() {
+            for card in &cards {
+                let buy = Buy::new(piece.clone(), card.clone());
+                if self.validate_buy(&buy).is_ok() {
+                    buys.push(Some(buy));
+                }
             }
         }
+        buys
     }
-    best
 }
 
-fn IDLE_POLICY(game: &Game, current_player_idx: usize) -> Option<Buy> {
-    let _current_player = &game.players[current_player_idx];
-    // println!("# {} policy: idles", current_player.name);
+fn cheapest_legal_buy(legal: &[Option<Buy>], piece: BuyablePiece) -> Option<Buy> {
+    legal
+        .iter()
+        .flatten()
+        .filter(|buy| buy.piece() == piece)
+        .min_by_key(|buy| buy.card().value())
+        .cloned()
+}
+
+fn IDLE_POLICY(_view: &PlayerView) -> Option<Buy> {
+    // println!("# {} policy: idles", view.name());
     None
 }
 
-fn KING_BUYER_POLICY(game: &Game, current_player_idx: usize) -> Option<Buy> {
-    let current_player = &game.players[current_player_idx];
-    if game.unbought_kings == 0 {
+fn KING_BUYER_POLICY(view: &PlayerView) -> Option<Buy> {
+    if view.unbought_kings() == 0 {
         // println!(
         //     "# {} policy: already happy since all kings are bought",
         //     current_player.name
@@ -166,17 +686,11 @@ fn KING_BUYER_POLICY(game: &Game, current_player_idx: usize) -> Option<Buy> {
     //     "# {} policy: wants to buy a king with cards: {:?}",
     //     current_player.name, current_player.hand
     // );
-    let next_king_cost = 5 - game.unbought_kings;
-    match cheapest_card_that_can_pay_x(&current_player.hand, next_king_cost) {
-        None => None,
-        Some(card) => Some(Buy::KingWith(card)),
-    }
+    cheapest_legal_buy(&view.legal_buys(), BuyablePiece::KING)
 }
 
-fn JACK_BUYER_POLICY(game: &Game, current_player_idx: usize) -> Option<Buy> {
-    let jacks_bought: i64 = game.players.iter().map(|p| p.jacks).sum();
-    let current_player = &game.players[current_player_idx];
-    if jacks_bought == 4 {
+fn JACK_BUYER_POLICY(view: &PlayerView) -> Option<Buy> {
+    if view.jacks_bought() == view.rules().jacks {
         // println!("# {} policy: no jacks left to buy", current_player.name);
         return None;
     }
@@ -184,162 +698,967 @@ fn JACK_BUYER_POLICY(game: &Game, current_player_idx: usize) -> Option<Buy> {
     //     "# {} policy: wants to buy a jack with cards: {:?}",
     //     current_player.name, current_player.hand
     // );
-    let next_jack_cost = jacks_bought + 1;
-    match cheapest_card_that_can_pay_x(&current_player.hand, next_jack_cost) {
-        None => None,
-        Some(card) => Some(Buy::JackWith(card)),
-    }
+    cheapest_legal_buy(&view.legal_buys(), BuyablePiece::JACK)
 }
 
-fn ONE_QUEEN_THEN_IDLE(game: &Game, current_player_idx: usize) -> Option<Buy> {
-    let queens_bought: i64 = game.players.iter().map(|p| p.queens).sum();
-    let current_player = &game.players[current_player_idx];
-    if current_player.queens > 0 {
-        return None;
-    } else if queens_bought == 4 {
+fn ONE_QUEEN_THEN_IDLE(view: &PlayerView) -> Option<Buy> {
+    if view.queens() > 0 {
         return None;
-    } else {
     }
-    let next_queen_cost = queens_bought + 1;
-    match cheapest_card_that_can_pay_x(&current_player.hand, next_queen_cost) {
-        None => None,
-        Some(card) => Some(Buy::QueenWith(card)),
+    cheapest_legal_buy(&view.legal_buys(), BuyablePiece::QUEEN)
+}
+
+/// The hand-written policies above.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BuiltinPolicy {
+    Idle,
+    KingBuyer,
+    JackBuyer,
+    OneQueenThenIdle,
+}
+
+impl Policy for BuiltinPolicy {
+    fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn choose_buy(&self, view: &PlayerView) -> Option<Buy> {
+        match self {
+            BuiltinPolicy::Idle => IDLE_POLICY(view),
+            BuiltinPolicy::KingBuyer => KING_BUYER_POLICY(view),
+            BuiltinPolicy::JackBuyer => JACK_BUYER_POLICY(view),
+            BuiltinPolicy::OneQueenThenIdle => ONE_QUEEN_THEN_IDLE(view),
+        }
     }
+
+    fn to_config(&self) -> Option<PolicyConfig> {
+        Some(PolicyConfig::Builtin(*self))
+    }
+}
+
+/// Where a player with queens puts the joker among the peeked cards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JokerPlacement {
+    /// Sorted in with the other cards, as the worthless card it is.
+    Unsorted,
+    /// Among the cards drawn `n` turns from now, 0 being this player's own draw. Goes as
+    /// deep as possible if the peek doesn't reach that turn.
+    TurnsAhead(usize),
+    /// As deep as the peek reaches.
+    Deepest,
+}
+
+/// Where a player with queens puts aces while drawing one still wipes the drawer's hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AceRouting {
+    /// Sorted in with the other cards by value.
+    Unsorted,
+    /// Into the draws of the opponent holding the most cards, then the next biggest hand,
+    /// and as deep as possible once no opponent draw is left.
+    BiggestOpponentHand,
+    /// As deep as the peek reaches.
+    Deepest,
+}
+
+/// How a player with queens orders the peeked cards. The default puts the highest card on
+/// top, which is what every stored record was scored with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReorderPolicy {
+    Heuristic(HeuristicReorder),
+    Lookahead(LookaheadReorder),
 }
 
-fn DEFAULT_REORDER_POLICY(game: &mut Game, current_player_idx: usize) {
-    if game.players[current_player_idx].queens == 0 {
-        return;
+impl Default for ReorderPolicy {
+    fn default() -> ReorderPolicy {
+        ReorderPolicy::Heuristic(HeuristicReorder::default())
     }
+}
 
-    let mut cards = Vec::new();
-    for _ in 0..game.players[current_player_idx].queens + 1 {
-        if !game.remaining_cards.is_empty() {
-            cards.push(game.remaining_cards.draw_top_card());
+impl ReorderPolicy {
+    /// The new order of the peeked cards, top card first. Only ever permutes `peeked`.
+    pub fn reorder(&self, view: &PlayerView, peeked: &[Card]) -> Vec<Card> {
+        match self {
+            ReorderPolicy::Heuristic(heuristic) => heuristic.reorder(view, peeked),
+            ReorderPolicy::Lookahead(lookahead) => lookahead.reorder(view, peeked),
         }
     }
+}
 
-    cards.sort_by(|a, b| a.value().cmp(&b.value()));
+/// Rule-based stacking of the peeked cards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeuristicReorder {
+    pub joker: JokerPlacement,
+    pub aces: AceRouting,
+    /// Deal the highest of the other cards to this player's own draws and the lowest to the
+    /// opponents', instead of simply stacking them highest first.
+    pub keep_high: bool,
+}
 
-    for c in cards {
-        game.remaining_cards.place_card_on_top(c);
+impl Default for HeuristicReorder {
+    fn default() -> HeuristicReorder {
+        HeuristicReorder {
+            joker: JokerPlacement::Unsorted,
+            aces: AceRouting::Unsorted,
+            keep_high: false,
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// For each of the top `n` cards, how many turns from now it gets drawn if nobody buys
+/// another jack: 0 for this player's own draw, 1 for the next player and so on.
+fn draw_turns(view: &PlayerView, n: usize) -> Vec<usize> {
+    let draws: Vec<usize> = std::iter::once(view.jacks())
+        .chain(view.opponents().map(|o| o.jacks))
+        .map(|jacks| 1 + jacks as usize)
+        .collect();
+    let mut turns = Vec::with_capacity(n);
+    let mut turn = 0;
+    while turns.len() < n {
+        turns.resize(turns.len() + draws[turn % draws.len()], turn);
+        turn += 1;
+    }
+    turns.truncate(n);
+    turns
+}
+
+impl HeuristicReorder {
+    pub fn reorder(&self, view: &PlayerView, peeked: &[Card]) -> Vec<Card> {
+        let route_aces = view.unbought_kings() > 0 && self.aces != AceRouting::Unsorted;
+        if self.joker == JokerPlacement::Unsorted && !route_aces && !self.keep_high {
+            let mut cards = peeked.to_vec();
+            cards.sort_by_key(|c| -c.value());
+            return cards;
+        }
+        let n = peeked.len();
+        let num_players = view.num_players();
+        let turns = draw_turns(view, n);
+        // The seat that draws slot `i`, counting from this player's.
+        let seat = |i: usize| turns[i] % num_players;
+        let mut slots: Vec<Option<Card>> = vec![None; n];
+        let mut aces = 0;
+        let mut rest = Vec::new();
+        for card in peeked {
+            match card {
+                Card::JOKER if self.joker != JokerPlacement::Unsorted => {
+                    let target = match self.joker {
+                        JokerPlacement::TurnsAhead(k) => turns.iter().position(|&t| t == k),
+                        _ => None,
+                    };
+                    slots[target.unwrap_or(n - 1)] = Some(Card::JOKER);
+                }
+                Card::ACE if route_aces => aces += 1,
+                _ => rest.push(card.clone()),
+            }
+        }
+
+        let mut ace_targets = Vec::new();
+        if self.aces == AceRouting::BiggestOpponentHand {
+            let mut opponents: Vec<(usize, usize)> = view
+                .opponents()
+                .enumerate()
+                .map(|(i, o)| (i + 1, o.hand_size))
+                .collect();
+            opponents.sort_by_key(|&(_, hand_size)| std::cmp::Reverse(hand_size));
+            for (offset, _) in opponents {
+                ace_targets.extend((0..n).filter(|&i| seat(i) == offset));
+            }
+        }
+        ace_targets.extend((0..n).rev());
+        for i in ace_targets {
+            if aces == 0 {
+                break;
+            }
+            if slots[i].is_none() {
+                slots[i] = Some(Card::ACE);
+                aces -= 1;
+            }
+        }
+
+        rest.sort_by_key(|c| -c.value());
+        let mut rest = VecDeque::from(rest);
+        if self.keep_high {
+            for (i, slot) in slots.iter_mut().enumerate() {
+                if slot.is_none() && seat(i) == 0 {
+                    *slot = rest.pop_front();
+                }
+            }
+            for slot in slots.iter_mut().filter(|slot| slot.is_none()) {
+                *slot = rest.pop_back();
+            }
+        } else {
+            for slot in slots.iter_mut().filter(|slot| slot.is_none()) {
+                *slot = rest.pop_front();
+            }
+        }
+        slots.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+/// Scores every distinct ordering of the peeked cards by playing the game out from the same
+/// sampled deals, and keeps the ordering that wins most often.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LookaheadReorder {
+    /// Deals sampled per decision; every ordering is scored on the same deals.
+    pub rollouts: usize,
+    /// How every player buys during the rollouts.
+    pub rollout_policy: BuyPolicyConfig,
+    /// Seeds the rollouts of a decision, together with the number of cards left.
+    pub seed: u64,
+}
+
+/// Longer peeks fall back to the default ordering; 5 cards already make 120 orderings.
+const MAX_LOOKAHEAD_PEEK: usize = 5;
+
+fn distinct_orderings(cards: &[Card]) -> Vec<Vec<Card>> {
+    if cards.is_empty() {
+        return vec![vec![]];
+    }
+    let mut orderings = Vec::new();
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].contains(card) {
+            continue;
+        }
+        let mut rest = cards.to_vec();
+        rest.remove(i);
+        for mut ordering in distinct_orderings(&rest) {
+            ordering.insert(0, card.clone());
+            orderings.push(ordering);
+        }
+    }
+    orderings
+}
+
+/// A guess at the cards hidden from a player: the opponents' hands, in `opponents()` order,
+/// and the draw pile below the peeked cards, top card first.
+struct SampledDeal {
+    opponent_hands: Vec<Vec<Card>>,
+    below_peek: Vec<Card>,
+    policy_seed: u64,
+}
+
+/// Deals the cards `view` can't see at random, leaving out its own hand, the peeked cards
+/// and the discards.
+fn sample_deal<R: Rng + ?Sized>(view: &PlayerView, peeked: &[Card], rng: &mut R) -> SampledDeal {
+    let ranks = [
+        Card::ACE,
+        Card::TWO,
+        Card::THREE,
+        Card::FOUR,
+        Card::FIVE,
+        Card::SIX,
+        Card::SEVEN,
+        Card::EIGHT,
+        Card::NINE,
+        Card::TEN,
+    ];
+    let mut pool = Vec::new();
+    for card in ranks.iter() {
+        for _ in 0..view.rules().copies_per_rank {
+            pool.push(card.clone());
+        }
+    }
+    for seen in view
+        .hand()
+        .cards()
+        .iter()
+        .chain(peeked)
+        .chain(view.discards())
+    {
+        if let Some(idx) = pool.iter().position(|c| c == seen) {
+            pool.swap_remove(idx);
+        }
+    }
+    pool.shuffle(rng);
+
+    let opponent_hands = view
+        .opponents()
+        .map(|o| pool.split_off(pool.len() - std::cmp::min(o.hand_size, pool.len())))
+        .collect();
+    let joker_below = !peeked.contains(&Card::JOKER);
+    let numbered_below = view.cards_left() - peeked.len() - joker_below as usize;
+    let mut below_peek = pool.split_off(pool.len() - std::cmp::min(numbered_below, pool.len()));
+    if joker_below {
+        below_peek.insert(rng.gen_range(0..=below_peek.len()), Card::JOKER);
+    }
+    SampledDeal {
+        opponent_hands,
+        below_peek,
+        policy_seed: rng.gen(),
+    }
+}
+
+/// Plays the game out from `view` with `ordering` on top of the sampled deal, and scores
+/// it for the viewing player: 1 for a win, 0.5 for a shared win.
+fn rollout(view: &PlayerView, ordering: &[Card], deal: &SampledDeal, policy: &dyn Policy) -> f64 {
+    let me = view.player_idx();
+    let mut players: Vec<Player> = (0..view.num_players())
+        .map(|seat| Player::new(format!("seat{}", seat), policy))
+        .collect();
+    players[me].hand.ccards = view.hand().cards().to_vec();
+    players[me].jacks = view.jacks();
+    players[me].queens = view.queens();
+    players[me].kings = view.kings();
+    for (opponent, hand) in view.opponents().zip(&deal.opponent_hands) {
+        let player = &mut players[opponent.player_idx];
+        player.hand.ccards = hand.clone();
+        player.jacks = opponent.jacks;
+        player.queens = opponent.queens;
+        player.kings = opponent.kings;
+    }
+    let mut deck = CardList::new();
+    deck.ccards = deal
+        .below_peek
+        .iter()
+        .rev()
+        .chain(ordering.iter().rev())
+        .cloned()
+        .collect();
+
+    let mut game = Game::new(players, view.rules(), deck);
+    game.seed_policy_rng(deal.policy_seed);
+    game.discards = view.discards().to_vec();
+    game.unbought_kings = view.unbought_kings();
+    game.current_player_idx = me;
+    game.turn = view.turn();
+    game.phase = Phase::Draw;
+    match verbose_play_game(&mut game) {
+        Ok(outcome) => match seat_outcomes(&outcome)[me] {
+            SeatOutcome::Win => 1.0,
+            SeatOutcome::Draw => 0.5,
+            _ => 0.0,
+        },
+        Err(_) => 0.0,
+    }
+}
+
+impl LookaheadReorder {
+    pub fn reorder(&self, view: &PlayerView, peeked: &[Card]) -> Vec<Card> {
+        if peeked.len() > MAX_LOOKAHEAD_PEEK {
+            return HeuristicReorder::default().reorder(view, peeked);
+        }
+        let mut orderings = distinct_orderings(peeked);
+        let mut rng = game_rng(self.seed, view.cards_left() as u64);
+        let deals: Vec<SampledDeal> = (0..self.rollouts)
+            .map(|_| sample_deal(view, peeked, &mut rng))
+            .collect();
+        let policy =
+            mk_player_from_config(self.rollout_policy.clone(), self.rollout_policy.clone());
+
+        let mut best_idx = 0;
+        let mut best_score = -1.0;
+        for (idx, ordering) in orderings.iter().enumerate() {
+            let score: f64 = deals
+                .iter()
+                .map(|deal| rollout(view, ordering, deal, &policy))
+                .sum();
+            if score > best_score {
+                best_idx = idx;
+                best_score = score;
+            }
+        }
+        orderings.swap_remove(best_idx)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BuyablePiece {
     JACK,
     QUEEN,
     KING,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+/// Whose face cards a `BuyPolicyItem::piece_num` counts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PieceCount {
+    /// The buyer's own pieces of that kind.
+    Own,
+    /// Every piece of that kind bought at the table.
+    Table,
+}
+
+impl PieceCount {
+    /// What `piece_num` counted before items said so explicitly: own jacks and queens, but
+    /// the table's kings.
+    pub fn implied_for(piece: &BuyablePiece) -> PieceCount {
+        match piece {
+            BuyablePiece::JACK | BuyablePiece::QUEEN => PieceCount::Own,
+            BuyablePiece::KING => PieceCount::Table,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct BuyPolicyItem {
     pub piece_type: BuyablePiece,
+    /// The item applies while `count` stands at `piece_num - 1` pieces of its kind.
     pub piece_num: i64,
+    pub count: PieceCount,
     pub budget: i64,
 }
 
 impl std::fmt::Debug for BuyPolicyItem {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let table = match self.count {
+            PieceCount::Own => "",
+            PieceCount::Table => "T",
+        };
         write!(
             f,
-            "{:?}#{}@{}",
-            self.piece_type, self.piece_num, self.budget
+            "{:?}#{}{}@{}",
+            self.piece_type, table, self.piece_num, self.budget
         )
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BuyPolicyConfig {
     pub priorities: Vec<BuyPolicyItem>,
+    #[serde(default)]
+    pub payment: PaymentStrategy,
+    #[serde(default)]
+    pub choice: ItemChoice,
 }
 
-fn next_jack_cost(game: &Game) -> i64 {
-    let jacks_bought: i64 = game.players.iter().map(|p| p.jacks).sum();
-    if jacks_bought == 4 {
-        return 1000;
-    } else {
-        return jacks_bought + 1;
+/// How `COSTED_POLICY` picks among the priority items it could buy right now. The random
+/// choices draw on `PlayerView::rng`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ItemChoice {
+    /// The first item in priority order.
+    #[default]
+    First,
+    /// Walks the items in priority order and takes each one with its probability, by index
+    /// into the priorities; items without a probability are always taken, and items with a
+    /// NaN probability never are.
+    Probabilities(Vec<f64>),
+    /// Softmax over priority scores, item `i` scoring `-i`: lower `temperature` favours the
+    /// first item more, and a temperature of 0 or less, or NaN, always takes it.
+    Softmax { temperature: f64 },
+}
+
+impl ItemChoice {
+    /// Chooses among `candidates`, the buyable items as (priority index, buy) in priority
+    /// order.
+    fn choose<I>(&self, view: &PlayerView, mut candidates: I) -> Option<Buy>
+    where
+        I: Iterator<Item = (usize, Buy)>,
+    {
+        match self {
+            ItemChoice::First => candidates.next().map(|(_, buy)| buy),
+            ItemChoice::Probabilities(probabilities) => {
+                for (idx, buy) in candidates {
+                    let p = match probabilities.get(idx) {
+                        Some(p) if p.is_nan() => 0.0,
+                        Some(p) => p.clamp(0.0, 1.0),
+                        None => 1.0,
+                    };
+                    if view.rng().gen_bool(p) {
+                        return Some(buy);
+                    }
+                }
+                None
+            }
+            ItemChoice::Softmax { temperature } if temperature.is_nan() || *temperature <= 0.0 => {
+                candidates.next().map(|(_, buy)| buy)
+            }
+            ItemChoice::Softmax { temperature } => {
+                let candidates: Vec<(usize, Buy)> = candidates.collect();
+                let first = candidates.first()?.0;
+                let weights: Vec<f64> = candidates
+                    .iter()
+                    .map(|(idx, _)| (-((idx - first) as f64) / temperature).exp())
+                    .collect();
+                let mut pick = view.rng().gen::<f64>() * weights.iter().sum::<f64>();
+                for ((_, buy), weight) in candidates.iter().zip(&weights) {
+                    if pick < *weight {
+                        return Some(buy.clone());
+                    }
+                    pick -= weight;
+                }
+                candidates.last().map(|(_, buy)| buy.clone())
+            }
+        }
     }
 }
 
-fn next_queen_cost(game: &Game) -> i64 {
-    let queens_bought: i64 = game.players.iter().map(|p| p.queens).sum();
-    if queens_bought == 4 {
-        return 1000;
-    } else {
-        return queens_bought + 1;
+/// Which card pays for a face card once a policy has decided to buy it. Only cards within
+/// the buy's budget that cover the cost are considered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum PaymentStrategy {
+    /// The lowest card that covers the cost.
+    #[default]
+    CheapestSufficient,
+    /// Only a card worth exactly the cost; without one the buy is skipped.
+    ExactCost,
+    /// The highest card, for when hand value doesn't matter yet.
+    Highest,
+    /// The lowest sufficient card that is not among the `k` highest cards in hand.
+    KeepTopK(usize),
+    /// The lowest sufficient card that is not an ace.
+    ProtectAces,
+}
+
+impl PaymentStrategy {
+    /// The legal buy of `piece` this strategy makes with a card from the hand, paying at
+    /// most `budget`. Apart from `KeepTopK`, this doesn't allocate.
+    pub fn pick(&self, view: &PlayerView, piece: BuyablePiece, budget: i64) -> Option<Buy> {
+        let cost = view.next_cost(&piece)?;
+        let mut candidates = view
+            .hand()
+            .cards()
+            .iter()
+            .filter(|card| card.value() >= cost && card.value() <= budget);
+        let value = |card: &&Card| card.value();
+        let card = match self {
+            PaymentStrategy::CheapestSufficient => candidates.min_by_key(value),
+            PaymentStrategy::ExactCost => candidates.find(|card| card.value() == cost),
+            PaymentStrategy::Highest => candidates.max_by_key(value),
+            PaymentStrategy::KeepTopK(k) => {
+                let mut hand: Vec<i64> = view.hand().cards().iter().map(|c| c.value()).collect();
+                hand.sort_unstable_by(|a, b| b.cmp(a));
+                let spare = &hand[std::cmp::min(*k, hand.len())..];
+                candidates
+                    .filter(|card| spare.contains(&card.value()))
+                    .min_by_key(value)
+            }
+            PaymentStrategy::ProtectAces => candidates
+                .filter(|card| **card != Card::ACE)
+                .min_by_key(value),
+        }?;
+        Some(Buy::new(piece, card.clone()))
     }
 }
 
-fn next_king_cost(game: &Game) -> i64 {
-    let kings_bought: i64 = 4 - game.unbought_kings;
-    if kings_bought == 4 {
-        return 1000;
+/// Cost of the next face card of a kind, or `None` once all `supply` copies are bought.
+fn next_cost(rules: &RulesConfig, bought: i64, supply: i64) -> Option<i64> {
+    if bought >= supply {
+        return None;
     } else {
-        return kings_bought + 1;
+        return Some(rules.first_cost + bought * rules.cost_step);
     }
 }
 
-fn COSTED_POLICY(game: &Game, current_player_idx: usize, policy: &BuyPolicyConfig) -> Option<Buy> {
-    let j_cost = next_jack_cost(&game);
-    let q_cost = next_queen_cost(&game);
-    let k_cost = next_king_cost(&game);
-    let kings_bought = 4 - game.unbought_kings;
-    let current_player = &game.players[current_player_idx];
+/// Why a `Buy` was rejected by `validate_buy`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IllegalBuyReason {
+    /// The payment card is not in the player's hand.
+    CardNotInHand,
+    /// Every copy of the face card has already been bought.
+    SoldOut,
+    /// The payment card is worth less than the face card costs.
+    Underpaid { cost: i64, paid: i64 },
+}
 
-    let (jack_cost, jack_opt) = match cheapest_card_that_can_pay_x(&current_player.hand, j_cost) {
-        None => (1000, None),
-        Some(card) => (card.value(), Some(Buy::JackWith(card))),
-    };
-    let (queen_cost, queen_opt) = match cheapest_card_that_can_pay_x(&current_player.hand, q_cost) {
-        None => (1000, None),
-        Some(card) => (card.value(), Some(Buy::QueenWith(card))),
-    };
-    let (king_cost, king_opt) = match cheapest_card_that_can_pay_x(&current_player.hand, k_cost) {
-        None => (1000, None),
-        Some(card) => (card.value(), Some(Buy::KingWith(card))),
-    };
+/// Checks a buy against the current face-card supply and costs.
+pub fn validate_buy(game: &Game, player_idx: usize, buy: &Buy) -> Result<(), IllegalBuyReason> {
+    game.view(player_idx).validate_buy(buy)
+}
 
-    for p in &policy.priorities {
-        match &p.piece_type {
-            BuyablePiece::JACK => {
-                if p.piece_num != current_player.jacks + 1 {
-                } else if p.budget < jack_cost {
-                } else {
-                    return jack_opt;
-                }
+/// Every legal action for the player, see `PlayerView::legal_buys`.
+pub fn legal_buys(game: &Game, player_idx: usize) -> Vec<Option<Buy>> {
+    game.view(player_idx).legal_buys()
+}
+
+fn COSTED_POLICY(view: &PlayerView, policy: &BuyPolicyConfig) -> Option<Buy> {
+    let candidates = policy.priorities.iter().enumerate().filter_map(|(idx, p)| {
+        let bought = match (&p.piece_type, p.count) {
+            (BuyablePiece::JACK, PieceCount::Own) => view.jacks(),
+            (BuyablePiece::JACK, PieceCount::Table) => view.jacks_bought(),
+            (BuyablePiece::QUEEN, PieceCount::Own) => view.queens(),
+            (BuyablePiece::QUEEN, PieceCount::Table) => view.queens_bought(),
+            (BuyablePiece::KING, PieceCount::Own) => view.kings(),
+            (BuyablePiece::KING, PieceCount::Table) => view.kings_bought(),
+        };
+        if p.piece_num != bought + 1 {
+            return None;
+        }
+        policy
+            .payment
+            .pick(view, p.piece_type.clone(), p.budget)
+            .map(|buy| (idx, buy))
+    });
+    policy.choice.choose(view, candidates)
+}
+
+/// Buys with `COSTED_POLICY`, following `base` while kings are left and `all_kings` once
+/// they are all bought.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostedPolicy {
+    pub base: BuyPolicyConfig,
+    pub all_kings: BuyPolicyConfig,
+    #[serde(default)]
+    pub reorder: ReorderPolicy,
+}
+
+impl Policy for CostedPolicy {
+    fn name(&self) -> String {
+        String::from("costed")
+    }
+
+    fn choose_buy(&self, view: &PlayerView) -> Option<Buy> {
+        if view.unbought_kings() == 0 {
+            COSTED_POLICY(view, &self.all_kings)
+        } else {
+            COSTED_POLICY(view, &self.base)
+        }
+    }
+
+    fn choose_reorder(&self, view: &PlayerView, peeked: &[Card]) -> Vec<Card> {
+        self.reorder.reorder(view, peeked)
+    }
+
+    fn to_config(&self) -> Option<PolicyConfig> {
+        Some(PolicyConfig::Costed(self.clone()))
+    }
+}
+
+/// A serializable description of a policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PolicyConfig {
+    Builtin(BuiltinPolicy),
+    Costed(CostedPolicy),
+    Rules(RulePolicy),
+    Phased(PhasedPolicy),
+}
+
+impl PolicyConfig {
+    pub fn build(&self) -> Box<dyn Policy> {
+        match self {
+            PolicyConfig::Builtin(policy) => Box::new(*policy),
+            PolicyConfig::Costed(policy) => Box::new(policy.clone()),
+            PolicyConfig::Rules(policy) => Box::new(policy.clone()),
+            PolicyConfig::Phased(policy) => Box::new(policy.clone()),
+        }
+    }
+}
+
+/// A number describing the game as the deciding player sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Feature {
+    HandTotal,
+    HandSize,
+    /// The value of the best card in hand, 0 for an empty hand.
+    HighestCard,
+    CardsLeft,
+    OwnJacks,
+    OwnQueens,
+    OwnKings,
+    /// The most jacks any single opponent owns.
+    MaxOpponentJacks,
+    MaxOpponentQueens,
+    /// Kings bought by all opponents together.
+    OpponentKings,
+    MaxOpponentHandSize,
+    JacksBought,
+    QueensBought,
+    KingsBought,
+    UnboughtKings,
+    /// Cost of the next face card of a kind, `i64::MAX` once it is sold out.
+    NextJackCost,
+    NextQueenCost,
+    NextKingCost,
+}
+
+impl Feature {
+    pub fn value(&self, view: &PlayerView) -> i64 {
+        let next_cost = |piece| view.next_cost(&piece).unwrap_or(i64::MAX);
+        match self {
+            Feature::HandTotal => view.hand().score_hand(),
+            Feature::HandSize => view.hand().len() as i64,
+            Feature::HighestCard => view
+                .hand()
+                .cards()
+                .iter()
+                .map(|c| c.value())
+                .max()
+                .unwrap_or(0),
+            Feature::CardsLeft => view.cards_left() as i64,
+            Feature::OwnJacks => view.jacks(),
+            Feature::OwnQueens => view.queens(),
+            Feature::OwnKings => view.kings(),
+            Feature::MaxOpponentJacks => view.opponents().map(|o| o.jacks).max().unwrap_or(0),
+            Feature::MaxOpponentQueens => view.opponents().map(|o| o.queens).max().unwrap_or(0),
+            Feature::OpponentKings => view.opponents().map(|o| o.kings).sum(),
+            Feature::MaxOpponentHandSize => view
+                .opponents()
+                .map(|o| o.hand_size as i64)
+                .max()
+                .unwrap_or(0),
+            Feature::JacksBought => view.jacks_bought(),
+            Feature::QueensBought => view.queens_bought(),
+            Feature::KingsBought => view.kings_bought(),
+            Feature::UnboughtKings => view.unbought_kings(),
+            Feature::NextJackCost => next_cost(BuyablePiece::JACK),
+            Feature::NextQueenCost => next_cost(BuyablePiece::QUEEN),
+            Feature::NextKingCost => next_cost(BuyablePiece::KING),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+impl Comparison {
+    pub fn holds(&self, lhs: i64, rhs: i64) -> bool {
+        match self {
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Ge => lhs >= rhs,
+            Comparison::Gt => lhs > rhs,
+        }
+    }
+}
+
+/// A condition on the game, built from feature comparisons.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Predicate {
+    Always,
+    Compare {
+        feature: Feature,
+        op: Comparison,
+        value: i64,
+    },
+    All(Vec<Predicate>),
+    Any(Vec<Predicate>),
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    pub fn holds(&self, view: &PlayerView) -> bool {
+        match self {
+            Predicate::Always => true,
+            Predicate::Compare { feature, op, value } => op.holds(feature.value(view), *value),
+            Predicate::All(predicates) => predicates.iter().all(|p| p.holds(view)),
+            Predicate::Any(predicates) => predicates.iter().any(|p| p.holds(view)),
+            Predicate::Not(predicate) => !predicate.holds(view),
+        }
+    }
+}
+
+/// What a `Rule` does once its predicate holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RuleAction {
+    /// Buy the piece with a card worth at most `budget`, chosen by `payment`. Without such a
+    /// card the next rule is tried.
+    Buy {
+        piece: BuyablePiece,
+        budget: i64,
+        #[serde(default)]
+        payment: PaymentStrategy,
+    },
+    /// Stop and buy nothing this turn.
+    Pass,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub when: Predicate,
+    pub then: RuleAction,
+}
+
+/// Tries its rules in priority order and takes the first action that applies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RulePolicy {
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub reorder: ReorderPolicy,
+}
+
+impl Policy for RulePolicy {
+    fn name(&self) -> String {
+        String::from("rules")
+    }
+
+    fn choose_buy(&self, view: &PlayerView) -> Option<Buy> {
+        for rule in &self.rules {
+            if !rule.when.holds(view) {
+                continue;
             }
-            BuyablePiece::QUEEN => {
-                if p.piece_num != current_player.queens + 1 {
-                } else if p.budget < queen_cost {
-                } else {
-                    return queen_opt;
+            match &rule.then {
+                RuleAction::Pass => return None,
+                RuleAction::Buy {
+                    piece,
+                    budget,
+                    payment,
+                } => {
+                    let buy = payment.pick(view, piece.clone(), *budget);
+                    if buy.is_some() {
+                        return buy;
+                    }
                 }
             }
-            BuyablePiece::KING => {
-                if p.piece_num != kings_bought + 1 {
-                } else if p.budget < king_cost {
-                } else {
-                    return king_opt;
-                }
+        }
+        None
+    }
+
+    fn choose_reorder(&self, view: &PlayerView, peeked: &[Card]) -> Vec<Card> {
+        self.reorder.reorder(view, peeked)
+    }
+
+    fn to_config(&self) -> Option<PolicyConfig> {
+        Some(PolicyConfig::Rules(self.clone()))
+    }
+}
+
+/// The rule form of a `BuyPolicyConfig`: each item buys its piece while its count stands at
+/// one fewer than `piece_num`.
+fn costed_rules(config: &BuyPolicyConfig, when: &Predicate) -> Vec<Rule> {
+    config
+        .priorities
+        .iter()
+        .map(|item| {
+            let owned = match (&item.piece_type, item.count) {
+                (BuyablePiece::JACK, PieceCount::Own) => Feature::OwnJacks,
+                (BuyablePiece::JACK, PieceCount::Table) => Feature::JacksBought,
+                (BuyablePiece::QUEEN, PieceCount::Own) => Feature::OwnQueens,
+                (BuyablePiece::QUEEN, PieceCount::Table) => Feature::QueensBought,
+                (BuyablePiece::KING, PieceCount::Own) => Feature::OwnKings,
+                (BuyablePiece::KING, PieceCount::Table) => Feature::KingsBought,
+            };
+            Rule {
+                when: Predicate::All(vec![
+                    when.clone(),
+                    Predicate::Compare {
+                        feature: owned,
+                        op: Comparison::Eq,
+                        value: item.piece_num - 1,
+                    },
+                ]),
+                then: RuleAction::Buy {
+                    piece: item.piece_type.clone(),
+                    budget: item.budget,
+                    payment: config.payment,
+                },
             }
+        })
+        .collect()
+}
+
+impl From<&CostedPolicy> for RulePolicy {
+    /// The same strategy as rules, as a starting point for searching over rule policies.
+    fn from(costed: &CostedPolicy) -> RulePolicy {
+        let kings_left = |op| Predicate::Compare {
+            feature: Feature::UnboughtKings,
+            op,
+            value: 0,
         };
+        let mut rules = costed_rules(&costed.base, &kings_left(Comparison::Gt));
+        rules.extend(costed_rules(&costed.all_kings, &kings_left(Comparison::Eq)));
+        RulePolicy {
+            rules,
+            reorder: costed.reorder.clone(),
+        }
     }
+}
 
-    None
+/// A point in the game from which a `PhasedPolicy` moves on to its next phase. Milestones
+/// are never undone once reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Milestone {
+    /// At least this many kings were bought at the table.
+    KingsBought(i64),
+    AllKingsBought,
+    /// At most this many cards are left in the draw pile.
+    CardsLeft(usize),
+    /// The player owns at least this many jacks.
+    OwnJacks(i64),
+    OwnQueens(i64),
+    /// The game reached this turn, counting from 0.
+    Turn(i64),
+}
+
+impl Milestone {
+    pub fn reached(&self, view: &PlayerView) -> bool {
+        match self {
+            Milestone::KingsBought(kings) => view.kings_bought() >= *kings,
+            Milestone::AllKingsBought => view.unbought_kings() == 0,
+            Milestone::CardsLeft(cards) => view.cards_left() <= *cards,
+            Milestone::OwnJacks(jacks) => view.jacks() >= *jacks,
+            Milestone::OwnQueens(queens) => view.queens() >= *queens,
+            Milestone::Turn(turn) => view.turn() >= *turn,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyPhase {
+    pub after: Milestone,
+    pub config: BuyPolicyConfig,
+}
+
+/// Buys with `COSTED_POLICY`, starting with `opening` and moving to each later phase once
+/// its milestone, and those of all phases before it, are reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhasedPolicy {
+    pub opening: BuyPolicyConfig,
+    pub later_phases: Vec<PolicyPhase>,
+    #[serde(default)]
+    pub reorder: ReorderPolicy,
+}
+
+impl PhasedPolicy {
+    pub fn current_config(&self, view: &PlayerView) -> &BuyPolicyConfig {
+        let mut config = &self.opening;
+        for phase in &self.later_phases {
+            if !phase.after.reached(view) {
+                break;
+            }
+            config = &phase.config;
+        }
+        config
+    }
+}
+
+impl Policy for PhasedPolicy {
+    fn name(&self) -> String {
+        String::from("phased")
+    }
+
+    fn choose_buy(&self, view: &PlayerView) -> Option<Buy> {
+        COSTED_POLICY(view, self.current_config(view))
+    }
+
+    fn choose_reorder(&self, view: &PlayerView, peeked: &[Card]) -> Vec<Card> {
+        self.reorder.reorder(view, peeked)
+    }
+
+    fn to_config(&self) -> Option<PolicyConfig> {
+        Some(PolicyConfig::Phased(self.clone()))
+    }
+}
+
+impl From<&CostedPolicy> for PhasedPolicy {
+    fn from(costed: &CostedPolicy) -> PhasedPolicy {
+        PhasedPolicy {
+            opening: costed.base.clone(),
+            later_phases: vec![PolicyPhase {
+                after: Milestone::AllKingsBought,
+                config: costed.all_kings.clone(),
+            }],
+            reorder: costed.reorder.clone(),
+        }
+    }
 }
 
 impl<'a> Player<'a> {
-    fn new(name: String, buy_policy: &BuyPolicyType) -> Player {
+    pub fn new(name: String, policy: &dyn Policy) -> Player<'_> {
         Player {
             name,
             hand: CardList::new(),
             jacks: 0,
             queens: 0,
-            buy_policy,
-            reorder_policy: DEFAULT_REORDER_POLICY,
+            kings: 0,
+            rejected_buys: 0,
+            known_top_cards: Vec::new(),
+            policy,
         }
     }
 }
@@ -351,16 +1670,19 @@ fn make_random_costed_policy<R: Rng + ?Sized>(rng: &mut R) -> Vec<BuyPolicyItem>
         policy.push(BuyPolicyItem {
             piece_type: BuyablePiece::JACK,
             piece_num: i,
+            count: PieceCount::Own,
             budget: rand_budget(),
         });
         policy.push(BuyPolicyItem {
             piece_type: BuyablePiece::QUEEN,
             piece_num: i,
+            count: PieceCount::Own,
             budget: rand_budget(),
         });
         policy.push(BuyPolicyItem {
             piece_type: BuyablePiece::KING,
             piece_num: i,
+            count: PieceCount::Table,
             budget: rand_budget(),
         });
     }
@@ -368,125 +1690,580 @@ fn make_random_costed_policy<R: Rng + ?Sized>(rng: &mut R) -> Vec<BuyPolicyItem>
     return policy;
 }
 
-fn init_deck<R: Rng + ?Sized>(rng: &mut R) -> CardList {
-    let mut cards = Vec::new();
-    cards.push(Card::JOKER);
-    for _ in 0..4 {
-        cards.push(Card::ACE);
-        cards.push(Card::TWO);
-        cards.push(Card::THREE);
-        cards.push(Card::FOUR);
-        cards.push(Card::FIVE);
-        cards.push(Card::SIX);
-        cards.push(Card::SEVEN);
-        cards.push(Card::EIGHT);
-        cards.push(Card::NINE);
-        cards.push(Card::TEN);
-    }
-    while cards.iter().position(|x| *x == Card::JOKER).unwrap() <= 13 {
-        cards.shuffle(rng);
+fn make_random_reorder_policy<R: Rng + ?Sized>(rng: &mut R) -> ReorderPolicy {
+    let joker = match rng.gen_range(0..3) {
+        0 => JokerPlacement::Unsorted,
+        1 => JokerPlacement::TurnsAhead(rng.gen_range(0..4)),
+        _ => JokerPlacement::Deepest,
+    };
+    let aces = match rng.gen_range(0..3) {
+        0 => AceRouting::Unsorted,
+        1 => AceRouting::BiggestOpponentHand,
+        _ => AceRouting::Deepest,
+    };
+    ReorderPolicy::Heuristic(HeuristicReorder {
+        joker,
+        aces,
+        keep_high: rng.gen_bool(0.5),
+    })
+}
+
+pub fn init_deck<R: Rng + ?Sized>(rng: &mut R, rules: &RulesConfig) -> CardList {
+    let mut cards = Vec::new();
+    cards.push(Card::JOKER);
+    for _ in 0..rules.copies_per_rank {
+        cards.push(Card::ACE);
+        cards.push(Card::TWO);
+        cards.push(Card::THREE);
+        cards.push(Card::FOUR);
+        cards.push(Card::FIVE);
+        cards.push(Card::SIX);
+        cards.push(Card::SEVEN);
+        cards.push(Card::EIGHT);
+        cards.push(Card::NINE);
+        cards.push(Card::TEN);
+    }
+    match rules.deck_setup {
+        DeckSetup::Reshuffle {
+            joker_free_top_cards,
+        } => {
+            assert!(
+                joker_free_top_cards < cards.len(),
+                "no room for the joker below the top {} of {} cards",
+                joker_free_top_cards,
+                cards.len()
+            );
+            while cards.iter().position(|x| *x == Card::JOKER).unwrap() < joker_free_top_cards {
+                cards.shuffle(rng);
+            }
+        }
+        DeckSetup::Split { top_fraction } => {
+            assert!(
+                (0.0..=1.0).contains(&top_fraction),
+                "top_fraction must be between 0 and 1, got {}",
+                top_fraction
+            );
+            let mut top = cards.split_off(1);
+            top.shuffle(rng);
+            let top_len = (top.len() as f64 * top_fraction).round() as usize;
+            let mut bottom = top.split_off(top_len);
+            bottom.append(&mut cards);
+            bottom.shuffle(rng);
+            top.append(&mut bottom);
+            cards = top;
+        }
+    }
+    cards.reverse();
+    let mut deck = CardList::new();
+    deck.ccards = cards;
+    return deck;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GameResult {
+    Paperclips,
+    Draw,
+    WinnerNamed(String),
+}
+
+/// The face cards one player bought during a game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaceCards {
+    pub jacks: i64,
+    pub queens: i64,
+    pub kings: i64,
+}
+
+/// Everything about how a game ended, indexed by seat. Scores and ranking are filled in
+/// even when the result is `Paperclips`, so margins can be compared across all games.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameOutcome {
+    pub result: GameResult,
+    /// Final hand score of every seat.
+    pub scores: Vec<i64>,
+    /// Seats from highest to lowest score; seats with equal scores share a group.
+    pub ranking: Vec<Vec<usize>>,
+    pub face_cards: Vec<FaceCards>,
+    /// Turns played, including the one in which the joker was drawn.
+    pub turns: i64,
+    pub joker_drawn_by: usize,
+}
+
+impl GameOutcome {
+    /// How far the winner finished ahead of the runner-up, if there is a single winner.
+    pub fn margin(&self) -> Option<i64> {
+        match (&self.result, self.ranking.first(), self.ranking.get(1)) {
+            (GameResult::WinnerNamed(_), Some(first), Some(second)) => {
+                Some(self.scores[first[0]] - self.scores[second[0]])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Everything that happens in a game, in order. Players are identified by seat index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// The players sat down in turn order and the draw pile was set up, top card first.
+    Start {
+        players: Vec<String>,
+        deck: Vec<Card>,
+        #[serde(default)]
+        rules: RulesConfig,
+    },
+    /// The player peeked at the top cards and put them back in this order, top card first.
+    Reorder {
+        player: usize,
+        cards: Vec<Card>,
+    },
+    Draw {
+        player: usize,
+        card: Card,
+    },
+    /// The player drew an ace before all kings were bought and discarded these cards.
+    AceWipe {
+        player: usize,
+        discarded: Vec<Card>,
+    },
+    Buy {
+        player: usize,
+        buy: Buy,
+    },
+    /// An illegal buy that was turned into a pass under `BuyValidation::Lenient`.
+    RejectedBuy {
+        player: usize,
+        buy: Buy,
+    },
+    JokerDrawn {
+        player: usize,
+    },
+    GameEnd {
+        result: GameResult,
+    },
+}
+
+impl GameEvent {
+    /// Whether the player in `seat` sees this event at a real table. Nobody sees the deck
+    /// being dealt, and draws and reorders are only seen by the player making them.
+    pub fn is_visible_to(&self, seat: usize) -> bool {
+        match self {
+            GameEvent::Start { .. } => false,
+            GameEvent::Reorder { player, .. } | GameEvent::Draw { player, .. } => *player == seat,
+            _ => true,
+        }
+    }
+}
+
+/// Ways a game can fail because a policy broke the engine's invariants.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameError {
+    /// The player had to draw a card but the draw pile was empty.
+    EmptyDeck { player: String },
+    /// The player's buy policy chose a buy that breaks the rules.
+    IllegalBuy {
+        player: String,
+        action: Buy,
+        reason: IllegalBuyReason,
+    },
+    /// A replayed event does not match the state rebuilt from the events before it.
+    ReplayDiverged { event_idx: usize, event: GameEvent },
+    /// The action does not answer the decision the game is waiting for.
+    UnexpectedAction { player: String, action: Action },
+    /// A reorder that is not a permutation of the peeked cards.
+    InvalidReorder { player: String, cards: Vec<Card> },
+}
+
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameError::EmptyDeck { player } => {
+                write!(f, "player {} had to draw from an empty deck", player)
+            }
+            GameError::IllegalBuy {
+                player,
+                action,
+                reason,
+            } => write!(
+                f,
+                "player {} chose illegal buy {:?}: {:?}",
+                player, action, reason
+            ),
+            GameError::ReplayDiverged { event_idx, event } => {
+                write!(f, "replay diverged at event {}: {:?}", event_idx, event)
+            }
+            GameError::UnexpectedAction { player, action } => write!(
+                f,
+                "player {} took {:?} when no such decision was pending",
+                player, action
+            ),
+            GameError::InvalidReorder { player, cards } => write!(
+                f,
+                "player {} reordered to {:?}, which is not a permutation of the peeked cards",
+                player, cards
+            ),
+        }
     }
-    cards.reverse();
-    let mut deck = CardList::new();
-    deck.ccards = cards;
-    return deck;
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
-pub enum GameResult {
-    Paperclips,
+impl std::error::Error for GameError {}
+
+/// Where a game is within the current player's turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Phase {
+    /// The player may rearrange the top cards if they own queens.
+    Reorder,
+    /// The player draws `1 + jacks` cards.
     Draw,
-    WinnerNamed(String),
+    /// The player may buy one face card.
+    Buy,
+    /// Play passes to the next player.
+    EndTurn,
+    Finished(GameOutcome),
 }
 
-fn verbose_play_game(mut game: &mut Game) -> GameResult {
-    let mut current_player_idx = 0;
-    'outer: loop {
-        // println!("| state: {:?}", game);
-        {
-            let current_player = &mut game.players[current_player_idx];
-            (current_player.reorder_policy)(&mut game, current_player_idx);
-        }
-        // println!("| reord: {:?}", game);
-
-        {
-            let current_player = &mut game.players[current_player_idx];
-            let cards_to_draw = 1 + current_player.jacks;
-            for _ in 0..cards_to_draw {
-                let draw = game.remaining_cards.draw_top_card();
-                // println!("player {} drew: {:?}", current_player.name, draw);
-                if draw == Card::JOKER {
-                    // println!("> and we have GAI");
-                    break 'outer;
-                } else if draw == Card::ACE && game.unbought_kings > 0 {
-                    // println!("> player {} discards their hand", current_player.name);
-                    current_player.hand = CardList::new();
+/// A choice the game is waiting for before it can continue.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// The player may put `peeked`, the top cards of the draw pile, back in any order.
+    Reorder { player: usize, peeked: Vec<Card> },
+    /// The player may make one of the `legal_buys` or pass.
+    Buy { player: usize },
+}
+
+/// An answer to a `Decision`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    /// The peeked cards in their new order, top card first.
+    Reorder(Vec<Card>),
+    Buy(Option<Buy>),
+}
+
+/// Whether `a` and `b` hold the same cards, counting each card value (the joker being 0).
+fn is_permutation(a: &[Card], b: &[Card]) -> bool {
+    let mut counts = [0i64; 11];
+    for card in a {
+        counts[card.value() as usize] += 1;
+    }
+    for card in b {
+        counts[card.value() as usize] -= 1;
+    }
+    counts.iter().all(|&count| count == 0)
+}
+
+impl<'a> Game<'a> {
+    /// The number of cards the player gets to look at before drawing.
+    fn peek_count(&self, player_idx: usize) -> usize {
+        let queens = self.players[player_idx].queens as usize;
+        if queens == 0 {
+            0
+        } else {
+            std::cmp::min(queens + 1, self.remaining_cards.ccards.len())
+        }
+    }
+
+    /// Runs the phases that need no decision until a decision is pending or the game ends.
+    fn advance(&mut self) -> Result<(), GameError> {
+        loop {
+            match self.phase {
+                Phase::Reorder => {
+                    if self.peek_count(self.current_player_idx) > 0 {
+                        return Ok(());
+                    }
+                    self.phase = Phase::Draw;
+                }
+                Phase::Draw => self.draw_cards()?,
+                Phase::Buy | Phase::Finished(_) => return Ok(()),
+                Phase::EndTurn => {
+                    self.current_player_idx += 1;
+                    if self.current_player_idx == self.players.len() {
+                        self.current_player_idx = 0;
+                    }
+                    self.turn += 1;
+                    self.phase = Phase::Reorder;
                 }
+            }
+        }
+    }
 
-                current_player.hand.place_card_on_top(draw.clone());
+    fn draw_cards(&mut self) -> Result<(), GameError> {
+        let current_player_idx = self.current_player_idx;
+        let cards_to_draw = 1 + self.players[current_player_idx].jacks;
+        for _ in 0..cards_to_draw {
+            let draw = match self.pop_top_card() {
+                Some(card) => card,
+                None => {
+                    return Err(GameError::EmptyDeck {
+                        player: self.players[current_player_idx].name.clone(),
+                    })
+                }
+            };
+            // println!("player {} drew: {:?}", current_player.name, draw);
+            if draw == Card::JOKER {
+                // println!("> and we have GAI");
+                self.emit(GameEvent::JokerDrawn {
+                    player: current_player_idx,
+                });
+                let outcome = self.final_outcome();
+                self.emit(GameEvent::GameEnd {
+                    result: outcome.result.clone(),
+                });
+                self.phase = Phase::Finished(outcome);
+                return Ok(());
+            } else if draw == Card::ACE && self.unbought_kings > 0 {
+                // println!("> player {} discards their hand", current_player.name);
+                let discarded =
+                    std::mem::replace(&mut self.players[current_player_idx].hand, CardList::new());
+                self.discards.extend(discarded.ccards.iter().cloned());
+                self.emit(GameEvent::AceWipe {
+                    player: current_player_idx,
+                    discarded: discarded.ccards,
+                });
             }
+
+            self.emit(GameEvent::Draw {
+                player: current_player_idx,
+                card: draw.clone(),
+            });
+            self.players[current_player_idx]
+                .hand
+                .place_card_on_top(draw);
         }
+        self.phase = Phase::Buy;
+        Ok(())
+    }
 
-        let current_player = &game.players[current_player_idx];
-        let action = (current_player.buy_policy)(&game, current_player_idx);
+    /// Moves a replayed game to `player_idx`'s turn, counting a new turn if play has
+    /// passed on since the last event.
+    fn enter_turn(&mut self, player_idx: usize) {
+        if player_idx != self.current_player_idx {
+            self.current_player_idx = player_idx;
+            self.turn += 1;
+        }
+    }
 
-        let current_player = &mut game.players[current_player_idx];
-        // println!("! player {} action: {:?}", current_player.name, action);
+    /// Plays every step that needs no decision, then returns the decision the game is
+    /// waiting for, or `None` once it is finished.
+    pub fn pending_decision(&mut self) -> Result<Option<Decision>, GameError> {
+        self.advance()?;
+        let player = self.current_player_idx;
+        Ok(match self.phase {
+            Phase::Reorder => Some(Decision::Reorder {
+                player,
+                peeked: self.top_cards(self.peek_count(player)),
+            }),
+            Phase::Buy => Some(Decision::Buy { player }),
+            _ => None,
+        })
+    }
 
-        match action {
-            Some(Buy::KingWith(card)) => {
-                current_player.hand.remove_card_of_type(card);
-                game.unbought_kings -= 1;
+    /// Answers the pending decision of the current player.
+    pub fn apply(&mut self, action: Action) -> Result<(), GameError> {
+        self.advance()?;
+        let current_player_idx = self.current_player_idx;
+        match (&self.phase, action) {
+            (Phase::Reorder, Action::Reorder(cards)) => {
+                let deck = &self.remaining_cards.ccards;
+                let peeked = &deck[deck.len() - self.peek_count(current_player_idx)..];
+                if !is_permutation(peeked, &cards) {
+                    return Err(GameError::InvalidReorder {
+                        player: self.players[current_player_idx].name.clone(),
+                        cards,
+                    });
+                }
+                self.reorder_top_cards(current_player_idx, &cards);
+                self.emit(GameEvent::Reorder {
+                    player: current_player_idx,
+                    cards,
+                });
+                self.phase = Phase::Draw;
             }
-            Some(Buy::JackWith(card)) => {
-                current_player.hand.remove_card_of_type(card);
-                current_player.jacks += 1;
+            (Phase::Buy, Action::Buy(action)) => {
+                // println!("! player {} action: {:?}", current_player.name, action);
+                if let Some(buy) = action {
+                    match validate_buy(self, current_player_idx, &buy) {
+                        Ok(()) => {
+                            self.apply_buy(current_player_idx, &buy);
+                            self.emit(GameEvent::Buy {
+                                player: current_player_idx,
+                                buy,
+                            });
+                        }
+                        Err(reason) => match self.buy_validation {
+                            BuyValidation::Strict => {
+                                return Err(GameError::IllegalBuy {
+                                    player: self.players[current_player_idx].name.clone(),
+                                    action: buy,
+                                    reason,
+                                })
+                            }
+                            BuyValidation::Lenient => {
+                                self.players[current_player_idx].rejected_buys += 1;
+                                self.emit(GameEvent::RejectedBuy {
+                                    player: current_player_idx,
+                                    buy,
+                                });
+                            }
+                        },
+                    }
+                }
+                self.phase = Phase::EndTurn;
             }
-            Some(Buy::QueenWith(card)) => {
-                current_player.hand.remove_card_of_type(card);
-                current_player.queens += 1;
+            (_, action) => {
+                return Err(GameError::UnexpectedAction {
+                    player: self.players[current_player_idx].name.clone(),
+                    action,
+                })
             }
-            _ => {}
         }
+        Ok(())
+    }
+
+    /// The result once the game is finished.
+    pub fn result(&self) -> Option<&GameResult> {
+        self.outcome().map(|outcome| &outcome.result)
+    }
 
-        current_player_idx += 1;
-        if current_player_idx == game.players.len() {
-            current_player_idx = 0;
+    /// The full outcome once the game is finished.
+    pub fn outcome(&self) -> Option<&GameOutcome> {
+        match &self.phase {
+            Phase::Finished(outcome) => Some(outcome),
+            _ => None,
         }
     }
-    if game.unbought_kings > 0 {
-        // println!("| state: {:?}", game);
-        // println!("the GAI turned humans into paperclips");
-        GameResult::Paperclips
-    } else {
+}
+
+/// Plays the game to the end, asking each player's policies for their decisions.
+pub fn verbose_play_game(game: &mut Game) -> Result<GameOutcome, GameError> {
+    while let Some(decision) = game.pending_decision()? {
         // println!("| state: {:?}", game);
-        // println!("scores:");
-        let mut highest_score = -1000;
-        let mut num_highest_score = -1;
-        for player in &game.players {
-            let score: i64 = player.hand.score_hand();
-            // println!("  {}: {}", player.name, score);
-            if score > highest_score {
-                highest_score = score;
-                num_highest_score = 0;
-            }
-            if score == highest_score {
-                num_highest_score += 1;
-            }
-        }
-        if num_highest_score >= 2 {
-            GameResult::Draw
-        } else {
-            let mut result = GameResult::Draw;
-            for player in &game.players {
-                let score: i64 = player.hand.score_hand();
-                if score == highest_score {
-                    result = GameResult::WinnerNamed(player.name.clone());
+        let action = match decision {
+            Decision::Reorder { player, peeked } => {
+                let cards = game.players[player]
+                    .policy
+                    .choose_reorder(&game.view(player), &peeked);
+                // println!("| reord: {:?}", game);
+                Action::Reorder(cards)
+            }
+            Decision::Buy { player } => {
+                Action::Buy(game.players[player].policy.choose_buy(&game.view(player)))
+            }
+        };
+        game.apply(action)?;
+    }
+    Ok(game.outcome().unwrap().clone())
+}
+
+/// Rebuilds the game recorded in `events` into `game`. The players of `game` only need to
+/// match in number: names, hands, face cards and the draw pile are all taken from the log,
+/// and the policies are never consulted.
+pub fn replay_events(game: &mut Game, events: &[GameEvent]) -> Result<(), GameError> {
+    for (event_idx, event) in events.iter().enumerate() {
+        let diverged = || GameError::ReplayDiverged {
+            event_idx,
+            event: event.clone(),
+        };
+        match event {
+            GameEvent::Start {
+                players,
+                deck,
+                rules,
+            } => {
+                if players.len() != game.players.len() {
+                    return Err(diverged());
+                }
+                for (player, name) in game.players.iter_mut().zip(players) {
+                    player.name = name.clone();
+                    player.hand = CardList::new();
+                    player.jacks = 0;
+                    player.queens = 0;
+                    player.kings = 0;
+                    player.rejected_buys = 0;
+                    player.known_top_cards.clear();
+                }
+                game.rules = rules.clone();
+                game.unbought_kings = rules.kings;
+                game.remaining_cards = CardList::new();
+                game.discards.clear();
+                for card in deck.iter().rev() {
+                    game.remaining_cards.place_card_on_top(card.clone());
+                }
+                game.current_player_idx = 0;
+                game.turn = 0;
+                game.phase = Phase::Reorder;
+            }
+            GameEvent::Reorder { player, cards } => {
+                if !is_permutation(&game.top_cards(cards.len()), cards) {
+                    return Err(diverged());
+                }
+                game.reorder_top_cards(*player, cards);
+                game.enter_turn(*player);
+                game.phase = Phase::Draw;
+            }
+            GameEvent::Draw { player, card } => {
+                if game.pop_top_card().as_ref() != Some(card) {
+                    return Err(diverged());
+                }
+                game.players[*player].hand.place_card_on_top(card.clone());
+                game.enter_turn(*player);
+                game.phase = Phase::Buy;
+            }
+            GameEvent::AceWipe { player, discarded } => {
+                if game.players[*player].hand.ccards != *discarded {
+                    return Err(diverged());
+                }
+                let wiped = std::mem::replace(&mut game.players[*player].hand, CardList::new());
+                game.discards.extend(wiped.ccards);
+            }
+            GameEvent::Buy { player, buy } => {
+                if validate_buy(game, *player, buy).is_err() {
+                    return Err(diverged());
+                }
+                game.apply_buy(*player, buy);
+                game.phase = Phase::EndTurn;
+            }
+            GameEvent::RejectedBuy { player, buy: _ } => {
+                game.players[*player].rejected_buys += 1;
+                game.phase = Phase::EndTurn;
+            }
+            GameEvent::JokerDrawn { player } => {
+                if game.pop_top_card() != Some(Card::JOKER) {
+                    return Err(diverged());
+                }
+                game.enter_turn(*player);
+            }
+            GameEvent::GameEnd { result } => {
+                if game.final_result() != *result {
+                    return Err(diverged());
                 }
+                game.phase = Phase::Finished(game.final_outcome());
             }
-            result
         }
     }
+    Ok(())
+}
+
+/// Writes events as JSONL, one event per line.
+pub fn write_events_jsonl<W: std::io::Write>(
+    events: &[GameEvent],
+    mut writer: W,
+) -> std::io::Result<()> {
+    for event in events {
+        serde_json::to_writer(&mut writer, event)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Reads events written by `write_events_jsonl`.
+pub fn read_events_jsonl<R: BufRead>(reader: R) -> std::io::Result<Vec<GameEvent>> {
+    let mut events = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            events.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(events)
 }
 
 // fn play_once() {
@@ -505,51 +2282,88 @@ fn verbose_play_game(mut game: &mut Game) -> GameResult {
 fn mk_player_from_config(
     base_config: BuyPolicyConfig,
     all_kings_config: BuyPolicyConfig,
-) -> BuyPolicyType {
-    return Box::new(move |game: &Game, current_player_idx: usize| {
-        if game.unbought_kings == 0 {
-            COSTED_POLICY(&game, current_player_idx, &all_kings_config)
-        } else {
-            COSTED_POLICY(&game, current_player_idx, &base_config)
-        }
-    });
+) -> CostedPolicy {
+    return CostedPolicy {
+        base: base_config,
+        all_kings: all_kings_config,
+        reorder: ReorderPolicy::default(),
+    };
 }
 
-fn mk_random_player<R: Rng + ?Sized>(rng: &mut R) -> (BuyPolicyType, BuyPolicyConfig) {
+fn mk_random_player<R: Rng + ?Sized>(rng: &mut R) -> CostedPolicy {
     let policy = BuyPolicyConfig {
         priorities: make_random_costed_policy(rng),
+        payment: PaymentStrategy::default(),
+        choice: ItemChoice::default(),
     };
-    let player = mk_player_from_config(policy.clone(), policy.clone());
-    return (player, policy);
+    return mk_player_from_config(policy.clone(), policy);
 }
 
-fn mk_random_player_all_kings<R: Rng + ?Sized>(
-    rng: &mut R,
-) -> (BuyPolicyType, BuyPolicyConfig, BuyPolicyConfig) {
+fn mk_random_player_all_kings<R: Rng + ?Sized>(rng: &mut R) -> CostedPolicy {
     let base_policy = BuyPolicyConfig {
         priorities: make_random_costed_policy(rng),
+        payment: PaymentStrategy::default(),
+        choice: ItemChoice::default(),
     };
     let kings_policy = BuyPolicyConfig {
         priorities: make_random_costed_policy(rng),
+        payment: PaymentStrategy::default(),
+        choice: ItemChoice::default(),
     };
-    let player = mk_player_from_config(base_policy.clone(), kings_policy.clone());
-    return (player, base_policy, kings_policy);
-}
-
-fn play_policies_against_each_other<R: Rng + ?Sized>(
-    rng: &mut R,
-    a: &BuyPolicyType,
-    b: &BuyPolicyType,
-) -> f64 {
-    let mut play = |x: &BuyPolicyType, y: &BuyPolicyType| -> u64 {
-        let result = verbose_play_game(&mut Game {
-            players: vec![
-                Player::new(String::from("first"), x),
-                Player::new(String::from("second"), y),
-            ],
-            unbought_kings: 4,
-            remaining_cards: init_deck(rng),
-        });
+    return mk_player_from_config(base_policy, kings_policy);
+}
+
+/// Portable, seedable RNG used for everything that has to be reproducible. Unlike `StdRng`,
+/// the ChaCha8 output stream is fixed across platforms and crate versions.
+pub type GameRng = ChaCha8Rng;
+
+/// The RNG for game number `game_idx` of an evaluation run with `master_seed`.
+pub fn game_rng(master_seed: u64, game_idx: u64) -> GameRng {
+    let mut rng = GameRng::seed_from_u64(master_seed);
+    rng.set_stream(game_idx);
+    rng
+}
+
+/// Derives an independent seed for part `idx` of a run seeded with `master_seed`.
+pub fn derive_seed(master_seed: u64, idx: u64) -> u64 {
+    game_rng(master_seed, idx).next_u64()
+}
+
+/// Plays game `game_idx` between `first` and `second`, seated in that order.
+fn play_pair_game(
+    rules: &RulesConfig,
+    seed: u64,
+    game_idx: u64,
+    first: &dyn Policy,
+    second: &dyn Policy,
+    record: bool,
+) -> Result<(GameResult, Option<Vec<GameEvent>>), GameError> {
+    let mut rng = game_rng(seed, game_idx);
+    let mut game = Game::new(
+        vec![
+            Player::new(String::from("first"), first),
+            Player::new(String::from("second"), second),
+        ],
+        rules,
+        init_deck(&mut rng, rules),
+    );
+    game.seed_policy_rng(rng.gen());
+    if record {
+        game.record_events();
+    }
+    let outcome = verbose_play_game(&mut game)?;
+    Ok((outcome.result, game.event_log))
+}
+
+/// Plays game `game_idx` with `a` moving first and game `game_idx + 1` with `b` moving first.
+fn play_policies_against_each_other(
+    rules: &RulesConfig,
+    seed: u64,
+    game_idx: u64,
+    a: &dyn Policy,
+    b: &dyn Policy,
+) -> Result<f64, GameError> {
+    let winner = |result: GameResult| -> u64 {
         match result {
             GameResult::WinnerNamed(name) => {
                 if name == "first" {
@@ -562,39 +2376,54 @@ fn play_policies_against_each_other<R: Rng + ?Sized>(
         }
     };
     let mut score = 0.0;
-    if play(a, b) == 1 {
+    if winner(play_pair_game(rules, seed, game_idx, a, b, false)?.0) == 1 {
         score += 0.5;
     }
-    if play(b, a) == 2 {
+    if winner(play_pair_game(rules, seed, game_idx + 1, b, a, false)?.0) == 2 {
         score += 0.5;
     }
-    score
+    Ok(score)
+}
+
+fn play_against_random_policy(
+    rules: &RulesConfig,
+    rng: &mut GameRng,
+    policy: &dyn Policy,
+    record: bool,
+) -> Result<(GameResult, Option<Vec<GameEvent>>), GameError> {
+    let random = mk_random_player(rng);
+    let players = if rng.gen_bool(0.5) {
+        vec![
+            Player::new(String::from("policy"), policy),
+            Player::new(String::from("random"), &random),
+        ]
+    } else {
+        vec![
+            Player::new(String::from("random"), &random),
+            Player::new(String::from("policy"), policy),
+        ]
+    };
+    let mut game = Game::new(players, rules, init_deck(rng, rules));
+    game.seed_policy_rng(rng.gen());
+    if record {
+        game.record_events();
+    }
+    let outcome = verbose_play_game(&mut game)?;
+    Ok((outcome.result, game.event_log))
 }
 
-fn eval_policy_against_random_policy<R: Rng + ?Sized>(
-    rng: &mut R,
+/// Plays `times` games against fresh random policies; game `i` draws its opponent, seating
+/// and deck from `game_rng(seed, i)`.
+fn eval_policy_against_random_policy(
+    rules: &RulesConfig,
+    seed: u64,
     times: i64,
-    policy: &BuyPolicyType,
-) -> f64 {
+    policy: &dyn Policy,
+) -> Result<f64, GameError> {
     let mut all_results = std::collections::HashMap::new();
-    for _ in 0..times {
-        let (random, _) = mk_random_player(rng);
-        let players = if rng.gen_bool(0.5) {
-            vec![
-                Player::new(String::from("policy"), policy),
-                Player::new(String::from("random"), &random),
-            ]
-        } else {
-            vec![
-                Player::new(String::from("random"), &random),
-                Player::new(String::from("policy"), policy),
-            ]
-        };
-        let result = verbose_play_game(&mut Game {
-            players,
-            unbought_kings: 4,
-            remaining_cards: init_deck(rng),
-        });
+    for game_idx in 0..times {
+        let (result, _) =
+            play_against_random_policy(rules, &mut game_rng(seed, game_idx as u64), policy, false)?;
         // println!("game result: {:?}", result);
         *all_results.entry(result).or_insert(0) += 1;
     }
@@ -603,7 +2432,241 @@ fn eval_policy_against_random_policy<R: Rng + ?Sized>(
         .get(&GameResult::WinnerNamed(String::from("policy")))
         .unwrap_or(&0);
     // println!("p1: {:?}", policy_score);
-    return policy_score as f64 / times as f64;
+    return Ok(policy_score as f64 / times as f64);
+}
+
+/// Replays game `game_idx` of `eval_policy_against_random_policy(seed, ..)` and returns its
+/// event log.
+pub fn reproduce_random_policy_game(
+    rules: &RulesConfig,
+    seed: u64,
+    game_idx: u64,
+    policy: &dyn Policy,
+) -> Result<Vec<GameEvent>, GameError> {
+    let (_, events) =
+        play_against_random_policy(rules, &mut game_rng(seed, game_idx), policy, true)?;
+    Ok(events.unwrap_or_default())
+}
+
+/// Replays one game of `eval_against_policy_set(seed, policy, tests)` against
+/// `tests[test_idx]`, the one where `policy` moved first or the one where it moved second,
+/// and returns its event log. `MetaEval::score` plays its 30% and 50% games with
+/// `derive_seed(seed, 2)` and `(seed, 3)`.
+pub fn reproduce_policy_set_game(
+    rules: &RulesConfig,
+    seed: u64,
+    test_idx: usize,
+    policy: &dyn Policy,
+    tests: &[Box<dyn Policy>],
+    policy_first: bool,
+) -> Result<Vec<GameEvent>, GameError> {
+    let game_idx = 2 * test_idx as u64;
+    let test = tests[test_idx].as_ref();
+    let (_, events) = if policy_first {
+        play_pair_game(rules, seed, game_idx, policy, test, true)?
+    } else {
+        play_pair_game(rules, seed, game_idx + 1, test, policy, true)?
+    };
+    Ok(events.unwrap_or_default())
+}
+
+/// How a single game ended from the point of view of one seat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeatOutcome {
+    Win,
+    Draw,
+    Loss,
+    Paperclips,
+}
+
+fn seat_outcomes(outcome: &GameOutcome) -> Vec<SeatOutcome> {
+    let top = &outcome.ranking[0];
+    (0..outcome.scores.len())
+        .map(|seat| match outcome.result {
+            GameResult::Paperclips => SeatOutcome::Paperclips,
+            _ if !top.contains(&seat) => SeatOutcome::Loss,
+            GameResult::WinnerNamed(_) => SeatOutcome::Win,
+            GameResult::Draw => SeatOutcome::Draw,
+        })
+        .collect()
+}
+
+/// Tally of game results for one policy sitting in one seat.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeatStats {
+    pub games: i64,
+    pub wins: i64,
+    pub draws: i64,
+    pub paperclips: i64,
+}
+
+impl SeatStats {
+    fn record(&mut self, outcome: SeatOutcome) {
+        self.games += 1;
+        match outcome {
+            SeatOutcome::Win => self.wins += 1,
+            SeatOutcome::Draw => self.draws += 1,
+            SeatOutcome::Paperclips => self.paperclips += 1,
+            SeatOutcome::Loss => {}
+        }
+    }
+
+    fn add(&mut self, other: &SeatStats) {
+        self.games += other.games;
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.paperclips += other.paperclips;
+    }
+
+    fn rate(&self, count: i64) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            count as f64 / self.games as f64
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        self.rate(self.wins)
+    }
+
+    pub fn draw_rate(&self) -> f64 {
+        self.rate(self.draws)
+    }
+
+    pub fn paperclip_rate(&self) -> f64 {
+        self.rate(self.paperclips)
+    }
+}
+
+/// Results of a table of 2-4 policies playing with seat rotation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableEvalResult {
+    /// `seats[policy_idx][seat_idx]` holds the results of policy `policy_idx` while it was
+    /// sitting in seat `seat_idx` (seat 0 moves first).
+    pub seats: Vec<Vec<SeatStats>>,
+}
+
+impl TableEvalResult {
+    fn new(num_players: usize) -> TableEvalResult {
+        TableEvalResult {
+            seats: vec![vec![SeatStats::default(); num_players]; num_players],
+        }
+    }
+
+    /// Results of one policy summed over all the seats it sat in.
+    pub fn overall(&self, policy_idx: usize) -> SeatStats {
+        let mut total = SeatStats::default();
+        for stats in &self.seats[policy_idx] {
+            total.add(stats);
+        }
+        total
+    }
+}
+
+/// Plays every seat rotation of `policies` on the same deck, dealt from
+/// `game_rng(seed, game_idx)`, so the seats only differ in who sits where.
+fn play_rotation(
+    rules: &RulesConfig,
+    seed: u64,
+    game_idx: u64,
+    policies: &[&dyn Policy],
+    results: &mut TableEvalResult,
+) -> Result<(), GameError> {
+    let num_players = policies.len();
+    let mut rng = game_rng(seed, game_idx);
+    let deck = init_deck(&mut rng, rules);
+    let policy_seed = rng.gen();
+    for rotation in 0..num_players {
+        let seating: Vec<usize> = (0..num_players)
+            .map(|seat| (seat + rotation) % num_players)
+            .collect();
+        let mut game = Game::new(
+            seating
+                .iter()
+                .map(|&policy_idx| Player::new(format!("p{}", policy_idx), policies[policy_idx]))
+                .collect(),
+            rules,
+            deck.clone(),
+        );
+        game.seed_policy_rng(policy_seed);
+        let outcome = verbose_play_game(&mut game)?;
+        for (seat, outcome) in seat_outcomes(&outcome).into_iter().enumerate() {
+            results.seats[seating[seat]][seat].record(outcome);
+        }
+    }
+    Ok(())
+}
+
+/// Plays `rounds` full seat rotations between 2-4 policies, so every policy sits in every
+/// seat `rounds` times. Round `r` deals all its games from `game_rng(seed, r)`.
+pub fn play_table(
+    rules: &RulesConfig,
+    seed: u64,
+    policies: &[&dyn Policy],
+    rounds: i64,
+) -> Result<TableEvalResult, GameError> {
+    assert!(
+        (2..=4).contains(&policies.len()),
+        "BAK is played by 2-4 players, got {}",
+        policies.len()
+    );
+    let mut results = TableEvalResult::new(policies.len());
+    for round in 0..rounds as u64 {
+        play_rotation(rules, seed, round, policies, &mut results)?;
+    }
+    Ok(results)
+}
+
+/// Seats `policy` at a table with `num_players - 1` random policies, drawing fresh
+/// opponents for every round of seat rotation. The evaluated policy is policy index 0 in
+/// the returned result. Round `r` draws its opponents from `game_rng(seed, 2 * r)` and deals
+/// its games from `game_rng(seed, 2 * r + 1)`.
+pub fn eval_policy_at_random_table(
+    rules: &RulesConfig,
+    seed: u64,
+    rounds: i64,
+    num_players: usize,
+    policy: &dyn Policy,
+) -> Result<TableEvalResult, GameError> {
+    assert!(
+        (2..=4).contains(&num_players),
+        "BAK is played by 2-4 players, got {}",
+        num_players
+    );
+    let mut results = TableEvalResult::new(num_players);
+    for round in 0..rounds as u64 {
+        let mut rng = game_rng(seed, 2 * round);
+        let randoms: Vec<CostedPolicy> = (1..num_players)
+            .map(|_| mk_random_player(&mut rng))
+            .collect();
+        let mut policies = vec![policy];
+        policies.extend(randoms.iter().map(|random| random as &dyn Policy));
+        play_rotation(rules, seed, 2 * round + 1, &policies, &mut results)?;
+    }
+    Ok(results)
+}
+
+/// Plays `rounds` seat rotations between players that all buy with `buy_policy` and only
+/// differ in how they reorder, e.g. to measure what a `LookaheadReorder` gains over the
+/// default. Policy `i` of the result reorders with `reorder_policies[i]`.
+pub fn compare_reorder_policies(
+    rules: &RulesConfig,
+    seed: u64,
+    rounds: i64,
+    buy_policy: &BuyPolicyConfig,
+    reorder_policies: &[ReorderPolicy],
+) -> Result<TableEvalResult, GameError> {
+    let policies: Vec<CostedPolicy> = reorder_policies
+        .iter()
+        .map(|reorder| CostedPolicy {
+            base: buy_policy.clone(),
+            all_kings: buy_policy.clone(),
+            reorder: reorder.clone(),
+        })
+        .collect();
+    let policies: Vec<&dyn Policy> = policies.iter().map(|p| p as &dyn Policy).collect();
+    play_table(rules, seed, &policies, rounds)
 }
 
 // fn play_many() {
@@ -673,10 +2736,61 @@ fn eval_policy_against_random_policy<R: Rng + ?Sized>(
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyEvalResult {
+    /// The opening phase of the policy.
     pub policy: BuyPolicyConfig,
+    /// The phase once all kings are bought, in records made before `phases` were stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub all_kings_policy: Option<BuyPolicyConfig>,
+    /// The phases that follow `policy`, see `PhasedPolicy`.
+    #[serde(default)]
+    pub phases: Vec<PolicyPhase>,
+    /// How the policy stacks peeked cards; records made before reordering was searched
+    /// over used the default.
+    #[serde(default)]
+    pub reorder_policy: ReorderPolicy,
     pub score: f64,
     pub times: i64,
+    /// Master seed of the evaluation, absent in records made before seeds were stored.
+    /// `check_meta_policies` generates the buy policies and then the reorder policy from
+    /// `game_rng(seed, 0)` and runs its random, 30% and 50% evaluations with
+    /// `derive_seed(seed, 1)`, `(seed, 2)` and `(seed, 3)`. Records of searches hold the seed
+    /// of the whole search, see `GeneticSearch::run`.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl PolicyEvalResult {
+    /// A record of `policy`, storing its later phases in `phases`.
+    pub fn from_phased(policy: PhasedPolicy, score: f64, times: i64, seed: Option<u64>) -> Self {
+        PolicyEvalResult {
+            policy: policy.opening,
+            all_kings_policy: None,
+            phases: policy.later_phases,
+            reorder_policy: policy.reorder,
+            score,
+            times,
+            seed,
+        }
+    }
+
+    /// The evaluated policy, reading an `all_kings_policy` as its only later phase.
+    pub fn phased_policy(&self) -> PhasedPolicy {
+        let mut later_phases = self.phases.clone();
+        if let Some(all_kings) = &self.all_kings_policy {
+            later_phases.insert(
+                0,
+                PolicyPhase {
+                    after: Milestone::AllKingsBought,
+                    config: all_kings.clone(),
+                },
+            );
+        }
+        PhasedPolicy {
+            opening: self.policy.clone(),
+            later_phases,
+            reorder: self.reorder_policy.clone(),
+        }
+    }
 }
 
 // p25: 168
@@ -764,27 +2878,127 @@ pub struct PolicyEvalResult {
 // p99: 463
 // p100: 583
 
-// fn play_policies_against_each_other(a: &BuyPolicyType, b: &BuyPolicyType) -> f64 {
+// fn play_policies_against_each_other(a: &dyn Policy, b: &dyn Policy) -> f64 {
 // 204866 policies >= 30%, 10444 policies >= 50%
 // 20% random, 60% 30+, 20% 50+
 // 60% => 204866
 // 20% => ~68288 => 6x policy_50
 
-fn eval_against_policy_set<R: Rng + ?Sized>(
-    rng: &mut R,
-    x: &BuyPolicyType,
-    tests: &Vec<BuyPolicyType>,
-) -> f64 {
+/// Plays `x` against every policy in `tests` from both seats; the games against `tests[i]`
+/// use `game_rng(seed, 2 * i)` and `game_rng(seed, 2 * i + 1)`.
+fn eval_against_policy_set(
+    rules: &RulesConfig,
+    seed: u64,
+    x: &dyn Policy,
+    tests: &[Box<dyn Policy>],
+) -> Result<f64, GameError> {
     let mut score = 0.0;
     let mut max = 0.0;
-    for t in tests {
-        score += play_policies_against_each_other(rng, x, t);
+    for (i, t) in tests.iter().enumerate() {
+        score += play_policies_against_each_other(rules, seed, 2 * i as u64, x, t.as_ref())?;
         max += 1.0;
     }
-    return score / max;
+    return Ok(score / max);
+}
+
+/// A `BuyPolicyItem` as records stored it before items had a `count`.
+#[derive(Deserialize)]
+struct LegacyPolicyItem {
+    piece_type: BuyablePiece,
+    piece_num: i64,
+    count: Option<PieceCount>,
+    budget: i64,
+}
+
+#[derive(Deserialize)]
+struct LegacyPolicyConfig {
+    priorities: Vec<LegacyPolicyItem>,
+}
+
+/// A `PolicyEvalResult` as records stored it before items had a `count`.
+#[derive(Deserialize)]
+struct LegacyEvalResult {
+    policy: LegacyPolicyConfig,
+    #[serde(default)]
+    all_kings_policy: Option<LegacyPolicyConfig>,
+    score: f64,
+    times: i64,
+}
+
+#[derive(Serialize)]
+struct MigratedPolicyConfig {
+    priorities: Vec<BuyPolicyItem>,
+}
+
+/// A migrated record, with the fields of the legacy record in the same order.
+#[derive(Serialize)]
+struct MigratedEvalResult {
+    policy: MigratedPolicyConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    all_kings_policy: Option<MigratedPolicyConfig>,
+    score: f64,
+    times: i64,
+}
+
+impl From<LegacyPolicyConfig> for MigratedPolicyConfig {
+    fn from(legacy: LegacyPolicyConfig) -> MigratedPolicyConfig {
+        MigratedPolicyConfig {
+            priorities: legacy
+                .priorities
+                .into_iter()
+                .map(|item| BuyPolicyItem {
+                    count: item
+                        .count
+                        .unwrap_or_else(|| PieceCount::implied_for(&item.piece_type)),
+                    piece_type: item.piece_type,
+                    piece_num: item.piece_num,
+                    budget: item.budget,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Tags every `BuyPolicyItem` of a JSONL.bz2 file of `PolicyEvalResult`s written before
+/// items had a `count` with the count it was scored with, see `PieceCount::implied_for`.
+/// Everything else is kept as it was. `output` is written through a temporary file, so it
+/// may be `input`. Returns the number of records written.
+pub fn migrate_piece_counts(input: &str, output: &str) -> std::io::Result<usize> {
+    let reader = std::io::BufReader::new(BzDecoder::new(std::fs::File::open(input)?));
+    let temp = format!("{}.tmp", output);
+    let mut writer = bzip2::write::BzEncoder::new(
+        std::io::BufWriter::new(std::fs::File::create(&temp)?),
+        Compression::best(),
+    );
+    let mut records = 0;
+    for line in reader.lines() {
+        let legacy: LegacyEvalResult = serde_json::from_str(&line?)?;
+        let record = MigratedEvalResult {
+            policy: legacy.policy.into(),
+            all_kings_policy: legacy.all_kings_policy.map(MigratedPolicyConfig::from),
+            score: legacy.score,
+            times: legacy.times,
+        };
+        serde_json::to_writer(&mut writer, &record)?;
+        std::io::Write::write_all(&mut writer, b"\n")?;
+        records += 1;
+    }
+    std::io::Write::flush(&mut writer.finish()?)?;
+    std::fs::rename(&temp, output)?;
+    Ok(records)
 }
 
-type StoredPolicy = Vec<Box<dyn Fn(&Game, usize) -> Option<Buy>>>;
+/// Every record of a JSONL.bz2 file of `PolicyEvalResult`s, such as those in `records/`.
+pub fn read_eval_results(path: &str) -> std::io::Result<Vec<PolicyEvalResult>> {
+    let reader = std::io::BufReader::new(BzDecoder::new(std::fs::File::open(path)?));
+    let mut results = vec![];
+    for line in reader.lines() {
+        results.push(serde_json::from_str(&line?)?);
+    }
+    Ok(results)
+}
+
+type StoredPolicy = Vec<Box<dyn Policy>>;
 
 pub fn read_policies() -> (StoredPolicy, StoredPolicy) {
     let file =
@@ -798,57 +3012,114 @@ pub fn read_policies() -> (StoredPolicy, StoredPolicy) {
             let line = line_or.unwrap();
             let result: PolicyEvalResult = serde_json::from_str(&line).unwrap();
             // eprintln!("score {} from policy: {:?}", result.score, result.policy);
-            policies_above_30.push(mk_player_from_config(
-                result.policy.clone(),
-                result.policy.clone(),
-            ));
+            let policy = CostedPolicy {
+                base: result.policy.clone(),
+                all_kings: result.policy.clone(),
+                reorder: result.reorder_policy.clone(),
+            };
             if result.score >= 0.5 {
-                policies_above_50.push(mk_player_from_config(
-                    result.policy.clone(),
-                    result.policy.clone(),
-                ));
+                policies_above_50.push(Box::new(policy.clone()) as Box<dyn Policy>);
             }
+            policies_above_30.push(Box::new(policy) as Box<dyn Policy>);
         }
     }
     (policies_above_30, policies_above_50)
 }
 
+/// How `check_meta_policies` scores a policy: 10% its win rate against fresh random
+/// policies, 20% its score against the recorded policies above 30% and 70% against those
+/// above 50%.
+pub struct MetaEval {
+    pub rules: RulesConfig,
+    /// Games against random policies in a full evaluation.
+    pub random_games: i64,
+    pub above_30: StoredPolicy,
+    pub above_50: StoredPolicy,
+}
+
+impl MetaEval {
+    /// The evaluation `check_meta_policies` runs, against the policies of `read_policies`.
+    pub fn from_records() -> MetaEval {
+        let (above_30, above_50) = read_policies();
+        MetaEval {
+            rules: RulesConfig::default(),
+            random_games: 140_000,
+            above_30,
+            above_50,
+        }
+    }
+
+    /// Scores `policy` on a `budget` fraction of a full evaluation: that share of the random
+    /// games and of the front of each policy set, at least one of each. The random, 30% and
+    /// 50% parts use `derive_seed(seed, 1)`, `(seed, 2)` and `(seed, 3)`.
+    pub fn score(&self, seed: u64, policy: &dyn Policy, budget: f64) -> Result<f64, GameError> {
+        let share =
+            |n: usize| std::cmp::min(n, std::cmp::max(1, (n as f64 * budget).ceil() as usize));
+        let random_score = eval_policy_against_random_policy(
+            &self.rules,
+            derive_seed(seed, 1),
+            share(self.random_games as usize) as i64,
+            policy,
+        )?;
+        let thirty_score = eval_against_policy_set(
+            &self.rules,
+            derive_seed(seed, 2),
+            policy,
+            &self.above_30[..share(self.above_30.len())],
+        )?;
+        let fifty_score = eval_against_policy_set(
+            &self.rules,
+            derive_seed(seed, 3),
+            policy,
+            &self.above_50[..share(self.above_50.len())],
+        )?;
+        // return Ok(random_score * 0.20 + thirty_score * 0.60 + fifty_score * 0.20);
+        return Ok(random_score * 0.10 + thirty_score * 0.20 + fifty_score * 0.70);
+    }
+}
+
 pub fn check_meta_policies() {
-    let mut rng = thread_rng();
     let mut histogram = Histogram::new();
 
-    let (policies_above_30, policies_above_50) = read_policies();
+    let meta = MetaEval::from_records();
 
     eprintln!(
         "{} policies >= 30%, {} policies >= 50%",
-        policies_above_30.len(),
-        policies_above_50.len()
+        meta.above_30.len(),
+        meta.above_50.len()
     );
 
     loop {
         // let (random_policy, random_policy_config) = mk_random_player();
-        let (random_policy, base_config, kings_config) =
-            mk_random_player_all_kings(&mut thread_rng());
-        let random_score = eval_policy_against_random_policy(&mut rng, 140_000, &random_policy);
-        let thirty_score = eval_against_policy_set(&mut rng, &random_policy, &policies_above_30);
-        let fifty_score = eval_against_policy_set(&mut rng, &random_policy, &policies_above_50);
-        // let combined_score = random_score * 0.20 + thirty_score * 0.60 + fifty_score * 0.20;
-        let combined_score = random_score * 0.10 + thirty_score * 0.20 + fifty_score * 0.70;
+        let seed: u64 = thread_rng().gen();
+        let mut policy_rng = game_rng(seed, 0);
+        let mut random_policy = mk_random_player_all_kings(&mut policy_rng);
+        random_policy.reorder = make_random_reorder_policy(&mut policy_rng);
+        let combined_score = match meta.score(seed, &random_policy, 1.0) {
+            Ok(score) => score,
+            Err(err) => {
+                eprintln!(
+                    "skipping policy {:?} then {:?} reordering {:?}: {}",
+                    random_policy.base, random_policy.all_kings, random_policy.reorder, err
+                );
+                continue;
+            }
+        };
         histogram
             .increment((combined_score * 1000.0) as u64)
             .expect("failed to register fast_score in histogram");
         eprintln!(
-            "policy with combined-score {}: {:?} then {:?}",
-            combined_score, base_config, kings_config
+            "policy with combined-score {}: {:?} then {:?} reordering {:?}",
+            combined_score, random_policy.base, random_policy.all_kings, random_policy.reorder
         );
         println!(
             "{}",
-            serde_json::to_string(&PolicyEvalResult {
-                policy: base_config,
-                all_kings_policy: Some(kings_config),
-                score: combined_score,
-                times: 0,
-            })
+            serde_json::to_string(&PolicyEvalResult::from_phased(
+                PhasedPolicy::from(&random_policy),
+                combined_score,
+                0,
+                Some(seed),
+            ))
             .unwrap()
         );
         if combined_score >= 0.1 {
@@ -864,13 +3135,134 @@ pub fn check_meta_policies() {
     }
 }
 
+/// How often the joker ended up at each depth of the draw pile (0 is the top card) for
+/// each of `setups`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckSetupReport {
+    pub samples: i64,
+    pub setups: Vec<DeckSetup>,
+    /// `joker_depths[setup_idx][depth]`
+    pub joker_depths: Vec<Vec<i64>>,
+}
+
+impl DeckSetupReport {
+    pub fn mean_depth(&self, setup_idx: usize) -> f64 {
+        let total: i64 = self.joker_depths[setup_idx]
+            .iter()
+            .enumerate()
+            .map(|(depth, count)| depth as i64 * count)
+            .sum();
+        total as f64 / self.samples as f64
+    }
+
+    /// Total variation distance between the joker-depth distributions of two setups.
+    pub fn distance(&self, a: usize, b: usize) -> f64 {
+        let diff: i64 = self.joker_depths[a]
+            .iter()
+            .zip(&self.joker_depths[b])
+            .map(|(x, y)| (x - y).abs())
+            .sum();
+        diff as f64 / 2.0 / self.samples as f64
+    }
+}
+
+impl std::fmt::Display for DeckSetupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (idx, setup) in self.setups.iter().enumerate() {
+            writeln!(
+                f,
+                "setup {}: {:?}, mean joker depth {:.2}",
+                idx,
+                setup,
+                self.mean_depth(idx)
+            )?;
+        }
+        let depths = self.joker_depths.iter().map(Vec::len).max().unwrap_or(0);
+        for depth in 0..depths {
+            let freqs: Vec<String> = self
+                .joker_depths
+                .iter()
+                .map(|counts| {
+                    let count = counts.get(depth).copied().unwrap_or(0);
+                    format!("{:.4}", count as f64 / self.samples as f64)
+                })
+                .collect();
+            writeln!(f, "depth {:2}: {}", depth, freqs.join("  "))?;
+        }
+        for b in 1..self.setups.len() {
+            writeln!(
+                f,
+                "total variation distance 0 vs {}: {:.4}",
+                b,
+                self.distance(0, b)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Deals `samples` decks with each setup (on top of otherwise default rules) and records
+/// where the joker lands. Sample `i` uses `game_rng(seed, i)` for every setup.
+pub fn compare_deck_setups(seed: u64, samples: i64, setups: &[DeckSetup]) -> DeckSetupReport {
+    let mut joker_depths = Vec::new();
+    for setup in setups {
+        let rules = RulesConfig {
+            deck_setup: setup.clone(),
+            ..RulesConfig::default()
+        };
+        let mut counts = Vec::new();
+        for sample in 0..samples {
+            let deck = init_deck(&mut game_rng(seed, sample as u64), &rules);
+            counts.resize(deck.ccards.len(), 0);
+            let depth = deck
+                .ccards
+                .iter()
+                .rev()
+                .position(|c| *c == Card::JOKER)
+                .unwrap();
+            counts[depth] += 1;
+        }
+        joker_depths.push(counts);
+    }
+    DeckSetupReport {
+        samples,
+        setups: setups.to_vec(),
+        joker_depths,
+    }
+}
+
+/// Compares the default reshuffle setup with the physical 1/3 split from rules.md and writes
+/// the report to `writer`.
+pub fn write_deck_setup_report<W: std::io::Write>(
+    samples: i64,
+    mut writer: W,
+) -> std::io::Result<()> {
+    let report = compare_deck_setups(
+        thread_rng().gen(),
+        samples,
+        &[
+            RulesConfig::default().deck_setup,
+            DeckSetup::Split {
+                top_fraction: 1.0 / 3.0,
+            },
+        ],
+    );
+    write!(writer, "{}", report)
+}
+
 pub fn run_profiling_test(policies: i64, times: i64) {
     // let mut rng = thread_rng();
-    let mut rng = StdRng::seed_from_u64(123);
+    let mut rng = GameRng::seed_from_u64(123);
     let mut total_scores = 0.0;
     for _i in 0..policies {
-        let (random_policy, _base_config, _kings_config) = mk_random_player_all_kings(&mut rng);
-        let random_score = eval_policy_against_random_policy(&mut rng, times, &random_policy);
+        let random_policy = mk_random_player_all_kings(&mut rng);
+        let random_score = eval_policy_against_random_policy(
+            &RulesConfig::default(),
+            rng.gen(),
+            times,
+            &random_policy,
+        )
+        .expect("random costed policies only make legal buys");
         total_scores += random_score;
     }
     eprintln!("total score: {}", total_scores);
@@ -898,3 +3290,81 @@ fn main() {
     // check_meta_policies();
     run_profiling_test(5, 10_000);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Snapshot = (Vec<(String, Vec<Card>, i64, i64, i64)>, Vec<Card>, i64, i64);
+
+    fn snapshot(game: &Game) -> Snapshot {
+        let players = game
+            .players
+            .iter()
+            .map(|p| {
+                (
+                    p.name.clone(),
+                    p.hand.ccards.clone(),
+                    p.jacks,
+                    p.queens,
+                    p.kings,
+                )
+            })
+            .collect();
+        (
+            players,
+            game.remaining_cards.ccards.clone(),
+            game.unbought_kings,
+            game.turn,
+        )
+    }
+
+    #[test]
+    fn replaying_a_jsonl_log_rebuilds_the_game() {
+        let rules = RulesConfig::default();
+        let queen_buyer = BuiltinPolicy::OneQueenThenIdle;
+        let king_buyer = BuiltinPolicy::KingBuyer;
+        for game_idx in 0..100 {
+            let mut game = Game::new(
+                vec![
+                    Player::new(String::from("queens"), &queen_buyer),
+                    Player::new(String::from("kings"), &king_buyer),
+                ],
+                &rules,
+                init_deck(&mut game_rng(5, game_idx), &rules),
+            );
+            game.record_events();
+            let outcome = verbose_play_game(&mut game).unwrap();
+            let events = game.event_log.clone().unwrap();
+            let reordered = events
+                .iter()
+                .any(|e| matches!(e, GameEvent::Reorder { .. }));
+            let wiped = events
+                .iter()
+                .any(|e| matches!(e, GameEvent::AceWipe { .. }));
+            if !reordered || !wiped {
+                continue;
+            }
+
+            let mut jsonl = Vec::new();
+            write_events_jsonl(&events, &mut jsonl).unwrap();
+            let read = read_events_jsonl(&jsonl[..]).unwrap();
+            assert_eq!(read, events);
+
+            let idle = BuiltinPolicy::Idle;
+            let mut replayed = Game::new(
+                vec![
+                    Player::new(String::from("a"), &idle),
+                    Player::new(String::from("b"), &idle),
+                ],
+                &RulesConfig::default(),
+                CardList::new(),
+            );
+            replay_events(&mut replayed, &read).unwrap();
+            assert_eq!(snapshot(&replayed), snapshot(&game));
+            assert_eq!(replayed.outcome(), Some(&outcome));
+            return;
+        }
+        panic!("no game had both a Queen reorder and an ace wipe");
+    }
+}
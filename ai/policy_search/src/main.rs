@@ -19,5 +19,9 @@ fn main() {
     // find_ok_random_policies();
     // find_fast_random_policies();
     bak_card_game::check_meta_policies();
+    // bak_card_game::search::run_genetic_search();
+    // bak_card_game::search::run_successive_halving();
+    // bak_card_game::search::polish_best_record("records/policies_scored_against_20random_60thirtyplus_20fiftyplus_meta.jsonl.bz2");
     // bak_card_game::run_profiling_test(5, 10_000);
+    // bak_card_game::write_deck_setup_report(1_000_000, std::io::stderr()).unwrap();
 }
@@ -0,0 +1,479 @@
+//! A plain-text syntax for buy policies, so strategies can be written, reviewed and diffed
+//! in ordinary files:
+//!
+//! ```text
+//! // comments run to the end of the line
+//! base:
+//!   KING#1@4
+//!   QUEEN#1@10
+//! all_kings:
+//!   JACK#2@3
+//! ```
+//!
+//! Each item is `PIECE#piece_num@budget`, the same tokens `BuyPolicyItem` prints with
+//! `Debug`. `piece_num` counts the buyer's own pieces, or every piece bought at the table
+//! when written with a `T`, as in `KING#T2@7`. A section may pick how it pays with a
+//! `pay=` token: `pay=cheapest` (the default), `pay=exact`, `pay=highest`, `pay=keep_top_2`
+//! or `pay=protect_aces`. It may also choose among the items it could buy at random with a
+//! `choose=` token: `choose=first` (the default), `choose=softmax_0.5` with a temperature, or
+//! `choose=probabilities_0.9_0.5` with the probability of each item in order.
+//!
+//! `base:` comes first and any number of later phases may follow, each starting at a
+//! `Milestone`: `all_kings:`, `after_kings_bought_2:`, `after_cards_left_30:`,
+//! `after_own_jacks_1:`, `after_own_queens_1:` or `after_turn_12:`.
+
+use crate::{
+    BuyPolicyConfig, BuyPolicyItem, BuyablePiece, ItemChoice, Milestone, PaymentStrategy,
+    PhasedPolicy, PieceCount, PolicyEvalResult, PolicyPhase,
+};
+
+/// A buy policy as written in a text file: the opening priorities and the phases that
+/// follow, as in `PhasedPolicy`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuyPolicyText {
+    pub base: BuyPolicyConfig,
+    pub later_phases: Vec<PolicyPhase>,
+}
+
+/// Where and why policy text failed to parse. Lines and columns count from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for PolicyParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for PolicyParseError {}
+
+fn error(line: usize, column: usize, message: String) -> PolicyParseError {
+    PolicyParseError {
+        line,
+        column,
+        message,
+    }
+}
+
+/// Parses a `PIECE#piece_num@budget` token that starts at `line`:`column`.
+fn parse_item(token: &str, line: usize, column: usize) -> Result<BuyPolicyItem, PolicyParseError> {
+    let hash = token.find('#').ok_or_else(|| {
+        error(
+            line,
+            column,
+            format!("expected `PIECE#num@budget`, found `{}`", token),
+        )
+    })?;
+    let piece_type = match &token[..hash] {
+        "JACK" => BuyablePiece::JACK,
+        "QUEEN" => BuyablePiece::QUEEN,
+        "KING" => BuyablePiece::KING,
+        other => {
+            return Err(error(
+                line,
+                column,
+                format!("unknown piece `{}`, expected JACK, QUEEN or KING", other),
+            ))
+        }
+    };
+    let at = token.find('@').ok_or_else(|| {
+        error(
+            line,
+            column + token.len(),
+            format!("expected `@budget` after `{}`", token),
+        )
+    })?;
+    let number = |start: usize, end: usize, what: &str| -> Result<i64, PolicyParseError> {
+        token[start..end].parse().map_err(|_| {
+            error(
+                line,
+                column + start,
+                format!("expected {}, found `{}`", what, &token[start..end]),
+            )
+        })
+    };
+    if at < hash {
+        return Err(error(line, column + at, String::from("`@` before `#`")));
+    }
+    let (count, num_start) = if token[hash + 1..].starts_with('T') {
+        (PieceCount::Table, hash + 2)
+    } else {
+        (PieceCount::Own, hash + 1)
+    };
+    Ok(BuyPolicyItem {
+        piece_type,
+        piece_num: number(num_start, at, "a piece number")?,
+        count,
+        budget: number(at + 1, token.len(), "a budget")?,
+    })
+}
+
+fn parse_payment(
+    name: &str,
+    line: usize,
+    column: usize,
+) -> Result<PaymentStrategy, PolicyParseError> {
+    match name {
+        "cheapest" => return Ok(PaymentStrategy::CheapestSufficient),
+        "exact" => return Ok(PaymentStrategy::ExactCost),
+        "highest" => return Ok(PaymentStrategy::Highest),
+        "protect_aces" => return Ok(PaymentStrategy::ProtectAces),
+        _ => {}
+    }
+    match name.strip_prefix("keep_top_").map(str::parse) {
+        Some(Ok(k)) => Ok(PaymentStrategy::KeepTopK(k)),
+        _ => Err(error(
+            line,
+            column,
+            format!(
+                "unknown payment `{}`, expected cheapest, exact, highest, keep_top_K or protect_aces",
+                name
+            ),
+        )),
+    }
+}
+
+fn payment_name(payment: &PaymentStrategy) -> String {
+    match payment {
+        PaymentStrategy::CheapestSufficient => String::from("cheapest"),
+        PaymentStrategy::ExactCost => String::from("exact"),
+        PaymentStrategy::Highest => String::from("highest"),
+        PaymentStrategy::KeepTopK(k) => format!("keep_top_{}", k),
+        PaymentStrategy::ProtectAces => String::from("protect_aces"),
+    }
+}
+
+fn parse_choice(name: &str, line: usize, column: usize) -> Result<ItemChoice, PolicyParseError> {
+    let unknown = || {
+        error(
+            line,
+            column,
+            format!(
+                "unknown choice `{}`, expected first, softmax_T or probabilities_P1_P2..",
+                name
+            ),
+        )
+    };
+    let finite = |number: &str| -> Result<f64, PolicyParseError> {
+        match number.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            Ok(_) => Err(error(
+                line,
+                column,
+                format!("`{}` in choice `{}` is not a finite number", number, name),
+            )),
+            Err(_) => Err(unknown()),
+        }
+    };
+    if name == "first" {
+        return Ok(ItemChoice::First);
+    }
+    if let Some(temperature) = name.strip_prefix("softmax_") {
+        let temperature = finite(temperature)?;
+        return Ok(ItemChoice::Softmax { temperature });
+    }
+    if name == "probabilities" {
+        return Ok(ItemChoice::Probabilities(vec![]));
+    }
+    let probabilities = name.strip_prefix("probabilities_").ok_or_else(unknown)?;
+    probabilities
+        .split('_')
+        .map(finite)
+        .collect::<Result<Vec<f64>, PolicyParseError>>()
+        .map(ItemChoice::Probabilities)
+}
+
+/// The parser only takes finite numbers, so NaN and infinite ones print as a finite number
+/// `ItemChoice::choose` treats the same way: NaN as 0, and infinities as the nearest
+/// probability or the largest or smallest temperature.
+fn choice_name(choice: &ItemChoice) -> String {
+    match choice {
+        ItemChoice::First => String::from("first"),
+        ItemChoice::Softmax { temperature } => {
+            let temperature = if temperature.is_nan() {
+                0.0
+            } else {
+                temperature.clamp(f64::MIN, f64::MAX)
+            };
+            format!("softmax_{}", temperature)
+        }
+        ItemChoice::Probabilities(probabilities) => {
+            let mut name = String::from("probabilities");
+            for p in probabilities {
+                let p = if p.is_nan() {
+                    0.0
+                } else if p.is_infinite() {
+                    p.clamp(0.0, 1.0)
+                } else {
+                    *p
+                };
+                name.push_str(&format!("_{}", p));
+            }
+            name
+        }
+    }
+}
+
+/// The milestone of an `all_kings:` or `after_<milestone>_<n>:` section header.
+fn parse_milestone(header: &str) -> Option<Milestone> {
+    if header == "all_kings" {
+        return Some(Milestone::AllKingsBought);
+    }
+    let rest = header.strip_prefix("after_")?;
+    let split = rest.rfind('_')?;
+    let (name, n) = (&rest[..split], &rest[split + 1..]);
+    match name {
+        "kings_bought" => n.parse().ok().map(Milestone::KingsBought),
+        "cards_left" => n.parse().ok().map(Milestone::CardsLeft),
+        "own_jacks" => n.parse().ok().map(Milestone::OwnJacks),
+        "own_queens" => n.parse().ok().map(Milestone::OwnQueens),
+        "turn" => n.parse().ok().map(Milestone::Turn),
+        _ => None,
+    }
+}
+
+fn milestone_header(milestone: &Milestone) -> String {
+    match milestone {
+        Milestone::AllKingsBought => String::from("all_kings"),
+        Milestone::KingsBought(n) => format!("after_kings_bought_{}", n),
+        Milestone::CardsLeft(n) => format!("after_cards_left_{}", n),
+        Milestone::OwnJacks(n) => format!("after_own_jacks_{}", n),
+        Milestone::OwnQueens(n) => format!("after_own_queens_{}", n),
+        Milestone::Turn(n) => format!("after_turn_{}", n),
+    }
+}
+
+fn empty_config() -> BuyPolicyConfig {
+    BuyPolicyConfig {
+        priorities: vec![],
+        payment: PaymentStrategy::default(),
+        choice: ItemChoice::default(),
+    }
+}
+
+impl std::str::FromStr for BuyPolicyItem {
+    type Err = PolicyParseError;
+
+    fn from_str(token: &str) -> Result<BuyPolicyItem, PolicyParseError> {
+        parse_item(token, 1, 1)
+    }
+}
+
+/// The whitespace-separated tokens of a line with their 1-based columns, up to any `//`.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let code = match line.find("//") {
+        Some(idx) => &line[..idx],
+        None => line,
+    };
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (idx, c) in code
+        .char_indices()
+        .chain(std::iter::once((code.len(), ' ')))
+    {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(idx),
+            (Some(s), true) => {
+                tokens.push((s + 1, &code[s..idx]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+impl BuyPolicyText {
+    pub fn parse(text: &str) -> Result<BuyPolicyText, PolicyParseError> {
+        let mut base: Option<BuyPolicyConfig> = None;
+        let mut later_phases: Vec<PolicyPhase> = vec![];
+        let mut last_line = 0;
+        for (line_idx, line) in text.lines().enumerate() {
+            let line_no = line_idx + 1;
+            last_line = line_no;
+            for (column, token) in tokens(line) {
+                if let Some(header) = token.strip_suffix(':') {
+                    if header == "base" {
+                        if base.is_some() {
+                            return Err(error(
+                                line_no,
+                                column,
+                                String::from("duplicate section `base:`"),
+                            ));
+                        }
+                        base = Some(empty_config());
+                        continue;
+                    }
+                    let after = parse_milestone(header).ok_or_else(|| {
+                        error(
+                            line_no,
+                            column,
+                            format!(
+                                "unknown section `{}`, expected `base:`, `all_kings:` or `after_<milestone>_<n>:`",
+                                token
+                            ),
+                        )
+                    })?;
+                    if base.is_none() {
+                        return Err(error(
+                            line_no,
+                            column,
+                            String::from("the `base:` section comes first"),
+                        ));
+                    }
+                    later_phases.push(PolicyPhase {
+                        after,
+                        config: empty_config(),
+                    });
+                    continue;
+                }
+                let section = match (later_phases.last_mut(), base.as_mut()) {
+                    (Some(phase), _) => &mut phase.config,
+                    (None, Some(base)) => base,
+                    (None, None) => {
+                        return Err(error(
+                            line_no,
+                            column,
+                            String::from("items must follow a `base:` section"),
+                        ))
+                    }
+                };
+                if let Some(name) = token.strip_prefix("pay=") {
+                    section.payment = parse_payment(name, line_no, column + 4)?;
+                } else if let Some(name) = token.strip_prefix("choose=") {
+                    section.choice = parse_choice(name, line_no, column + 7)?;
+                } else {
+                    section.priorities.push(parse_item(token, line_no, column)?);
+                }
+            }
+        }
+        match base {
+            Some(base) => Ok(BuyPolicyText { base, later_phases }),
+            None => Err(error(
+                last_line + 1,
+                1,
+                String::from("missing `base:` section"),
+            )),
+        }
+    }
+
+    /// The policy this text describes, with the default reorder policy.
+    pub fn to_policy(&self) -> PhasedPolicy {
+        PhasedPolicy {
+            opening: self.base.clone(),
+            later_phases: self.later_phases.clone(),
+            reorder: Default::default(),
+        }
+    }
+}
+
+impl From<&PolicyEvalResult> for BuyPolicyText {
+    fn from(result: &PolicyEvalResult) -> BuyPolicyText {
+        let policy = result.phased_policy();
+        BuyPolicyText {
+            base: policy.opening,
+            later_phases: policy.later_phases,
+        }
+    }
+}
+
+/// Prints one item per line, so policies diff cleanly; parses back to the same value.
+impl std::fmt::Display for BuyPolicyText {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut section = |name: &str, config: &BuyPolicyConfig| -> std::fmt::Result {
+            writeln!(f, "{}:", name)?;
+            if config.payment != PaymentStrategy::default() {
+                writeln!(f, "  pay={}", payment_name(&config.payment))?;
+            }
+            if config.choice != ItemChoice::default() {
+                writeln!(f, "  choose={}", choice_name(&config.choice))?;
+            }
+            for item in &config.priorities {
+                writeln!(f, "  {:?}", item)?;
+            }
+            Ok(())
+        };
+        section("base", &self.base)?;
+        for phase in &self.later_phases {
+            section(&milestone_header(&phase.after), &phase.config)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "base:
+  pay=keep_top_2
+  choose=softmax_0.5
+  KING#T2@7
+  QUEEN#1@10
+  JACK#2@3
+all_kings:
+  choose=probabilities_0.9_0.25
+  JACK#T3@4
+after_cards_left_30:
+  pay=protect_aces
+  QUEEN#2@8
+";
+
+    fn parse_error(text: &str) -> (usize, usize) {
+        let err = BuyPolicyText::parse(text).unwrap_err();
+        (err.line, err.column)
+    }
+
+    #[test]
+    fn prints_and_parses_back() {
+        let policy = BuyPolicyText::parse(TEXT).unwrap();
+        assert_eq!(
+            policy.base.priorities[0],
+            BuyPolicyItem {
+                piece_type: BuyablePiece::KING,
+                piece_num: 2,
+                count: PieceCount::Table,
+                budget: 7,
+            }
+        );
+        assert_eq!(policy.base.payment, PaymentStrategy::KeepTopK(2));
+        assert_eq!(policy.base.choice, ItemChoice::Softmax { temperature: 0.5 });
+        assert_eq!(policy.later_phases.len(), 2);
+        assert_eq!(policy.later_phases[1].after, Milestone::CardsLeft(30));
+        assert_eq!(policy.to_string(), TEXT);
+        assert_eq!(BuyPolicyText::parse(&policy.to_string()).unwrap(), policy);
+    }
+
+    #[test]
+    fn non_finite_choices_print_as_numbers_that_parse() {
+        let mut policy = BuyPolicyText::parse(TEXT).unwrap();
+        policy.base.choice = ItemChoice::Softmax {
+            temperature: f64::NAN,
+        };
+        policy.later_phases[0].config.choice =
+            ItemChoice::Probabilities(vec![f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 0.5]);
+        let parsed = BuyPolicyText::parse(&policy.to_string()).unwrap();
+        assert_eq!(parsed.base.choice, ItemChoice::Softmax { temperature: 0.0 });
+        assert_eq!(
+            parsed.later_phases[0].config.choice,
+            ItemChoice::Probabilities(vec![0.0, 1.0, 0.0, 0.5])
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_line_and_column() {
+        assert_eq!(parse_error("KING#1@4"), (1, 1));
+        assert_eq!(parse_error("base:\n  KING#1@x"), (2, 10));
+        assert_eq!(parse_error("base:\n\n   ROOK#1@2"), (3, 4));
+        assert_eq!(parse_error("base:\n  KING#T@2"), (2, 9));
+        assert_eq!(parse_error("base:\n  JACK#1@2 pay=lowest"), (2, 16));
+        assert_eq!(parse_error("base:\n  choose=softmax_inf"), (2, 10));
+        assert_eq!(parse_error("base:\nall_kings:\nbase:"), (3, 1));
+        assert_eq!(parse_error("base:\n  after_jokers_1:"), (2, 3));
+        assert_eq!(parse_error("// no sections\n"), (2, 1));
+    }
+}